@@ -1,16 +1,25 @@
 // 类型定义
 export type {
+  DiffReview,
+  FormField,
   ImageData,
   IPopupManager,
   McpRequest,
+  NotifyRequest,
   PopupConfig,
   PopupEvent,
+  PopupOption,
+  PopupQuestion,
   PopupResponse,
   PopupState,
 } from '../../types/popup'
 // 弹窗组件导出
 export { default as McpPopup } from './McpPopup.vue'
+export { default as NotifyToast } from './NotifyToast.vue'
 export { default as PopupActions } from './PopupActions.vue'
 export { default as PopupContent } from './PopupContent.vue'
+export { default as PopupForm } from './PopupForm.vue'
 export { default as PopupHeader } from './PopupHeader.vue'
 export { default as PopupInput } from './PopupInput.vue'
+export { default as PopupQuestions } from './PopupQuestions.vue'
+export { default as PopupReview } from './PopupReview.vue'
@@ -47,7 +47,13 @@ export function useAppInitialization(mcpHandler: ReturnType<typeof import('./use
       ])
 
       // 检查是否为MCP模式
-      const { isMcp, mcpContent } = await checkMcpMode()
+      const { isMcp, isNotify, mcpContent } = await checkMcpMode()
+
+      // 通知窗口的尺寸和位置由后端设置，不加载窗口配置
+      if (isNotify) {
+        isInitializing.value = false
+        return { isMcp, mcpContent }
+      }
 
       // 无论是否为MCP模式，都加载窗口设置
       await settings.loadWindowSettings()
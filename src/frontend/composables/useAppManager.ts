@@ -83,6 +83,7 @@ export function useAppManager() {
     naiveTheme: theme.naiveTheme,
     mcpRequest: mcpHandler.mcpRequest,
     showMcpPopup: mcpHandler.showMcpPopup,
+    notification: mcpHandler.notification,
     appConfig,
     isInitializing: appInit.isInitializing,
 
@@ -1,13 +1,29 @@
+import type { McpRequest } from '../types/popup'
 import { invoke } from '@tauri-apps/api/core'
 import { listen } from '@tauri-apps/api/event'
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow'
 import { ref } from 'vue'
 
+const DEFAULT_WINDOW_TITLE = '寸止'
+
+/**
+ * 调用方标签，形如 "Cursor / foo"，客户端和项目都未知时返回空字符串
+ */
+export function getCallerLabel(request?: McpRequest | null) {
+  if (!request) {
+    return ''
+  }
+  const project = request.project_path?.replace(/[/\\]+$/, '').split(/[/\\]/).pop()
+  return [request.client?.name, project].filter(Boolean).join(' / ')
+}
+
 /**
  * MCP处理组合式函数
  */
 export function useMcpHandler() {
   const mcpRequest = ref(null)
   const showMcpPopup = ref(false)
+  const notification = ref(null)
 
   /**
    * 统一的MCP响应处理
@@ -62,6 +78,15 @@ export function useMcpHandler() {
       // 设置请求数据和显示状态
       mcpRequest.value = request
       showMcpPopup.value = true
+
+      // 多个代理同时提问时，在窗口标题中标明调用方
+      const label = getCallerLabel(request)
+      try {
+        await getCurrentWebviewWindow().setTitle(label ? `${DEFAULT_WINDOW_TITLE} - ${label}` : DEFAULT_WINDOW_TITLE)
+      }
+      catch (error) {
+        console.error('设置窗口标题失败:', error)
+      }
     }
     else {
       console.log('🔕 跳过前端弹窗显示，仅使用Telegram交互')
@@ -75,20 +100,7 @@ export function useMcpHandler() {
       console.error('播放音频通知失败:', error)
     }
 
-    // 启动Telegram同步（无论是否显示弹窗都启动）
-    try {
-      if (request?.message) {
-        await invoke('start_telegram_sync', {
-          message: request.message,
-          predefinedOptions: request.predefined_options || [],
-          isMarkdown: request.is_markdown || false,
-        })
-        console.log('✅ Telegram同步启动成功')
-      }
-    }
-    catch (error) {
-      console.error('启动Telegram同步失败:', error)
-    }
+    // Telegram 由 MCP 服务器作为独立渠道同时提问，先作答的一方胜出，这里无需再同步
   }
 
   /**
@@ -98,6 +110,18 @@ export function useMcpHandler() {
     try {
       const args = await invoke('get_cli_args')
 
+      // 桌面通知模式：显示通知并播放提示音，由后端到时退出
+      if (args && (args as any).notify) {
+        notification.value = await invoke('read_mcp_request', { filePath: (args as any).notify })
+        try {
+          await invoke('play_notification_sound')
+        }
+        catch (error) {
+          console.error('播放音频通知失败:', error)
+        }
+        return { isMcp: true, isNotify: true, mcpContent: null }
+      }
+
       if (args && (args as any).mcp_request) {
         // 读取MCP请求文件
         const content = await invoke('read_mcp_request', { filePath: (args as any).mcp_request })
@@ -105,13 +129,13 @@ export function useMcpHandler() {
         if (content) {
           await showMcpDialog(content)
         }
-        return { isMcp: true, mcpContent: content }
+        return { isMcp: true, isNotify: false, mcpContent: content }
       }
     }
     catch (error) {
       console.error('检查MCP模式失败:', error)
     }
-    return { isMcp: false, mcpContent: null }
+    return { isMcp: false, isNotify: false, mcpContent: null }
   }
 
   /**
@@ -122,6 +146,18 @@ export function useMcpHandler() {
       await listen('mcp-request', (event) => {
         showMcpDialog(event.payload)
       })
+
+      // 常驻模式：队列清空或请求被撤回时关闭弹窗
+      await listen('mcp-queue-idle', () => {
+        showMcpPopup.value = false
+        mcpRequest.value = null
+      })
+      await listen('mcp-request-withdrawn', (event) => {
+        if ((mcpRequest.value as any)?.id === event.payload) {
+          showMcpPopup.value = false
+          mcpRequest.value = null
+        }
+      })
     }
     catch (error) {
       console.error('设置MCP事件监听器失败:', error)
@@ -131,6 +167,7 @@ export function useMcpHandler() {
   return {
     mcpRequest,
     showMcpPopup,
+    notification,
     handleMcpResponse,
     handleMcpCancel,
     showMcpDialog,
@@ -27,6 +27,8 @@ export const PROMPT_SECTIONS = {
     base: `- 只能通过MCP \`寸止\` 对我进行询问，禁止直接询问或结束任务询问`,
     detail: `寸止工具使用细节：
 - 需求不明确时使用 \`寸止\` 询问澄清，提供预定义选项
+- 有多个相互独立的问题时，通过 \`questions\` 一次性提问，回答以问题ID为键返回，避免反复打断
+- 调用 \`寸止\` 时传入 \`project_path\`（git 根目录），以便记录交互历史
 - 在有多个方案的时候，需要使用 \`寸止\` 询问，而不是自作主张
 - 在有方案/策略需要更新时，需要使用 \`寸止\` 询问，而不是自作主张
 - 即将完成请求前必须调用 \`寸止\` 请求反馈
@@ -37,10 +39,15 @@ export const PROMPT_SECTIONS = {
   memory: {
     base: ``,
     detail: `记忆管理使用细节：
-- 对话开始时查询 \`回忆\` 参数 \`project_path\` 为 git 的根目录
+- 对话开始时查询 \`回忆\` 参数 \`project_path\` 为 git 的根目录，可用 \`query\` 填写当前任务的关键词、\`categories\` 限定分类，只获取相关记忆
 - 当发现用户输入"请记住："时，要对用户的消息进行总结后调用 \`记忆\` 的 add 功能添加记忆
 - 使用 \`记忆\` 的 add 功能添加新记忆（content + category: rule/preference/pattern/context）
-- 仅在重要变更时更新记忆，保持简洁`,
+- 与具体项目无关的个人偏好（如回复语言、惯用库）使用 \`scope: global\` 保存为全局记忆，回忆时会自动合并
+- 添加记忆时如提示与已有记忆冲突，使用 \`zhi\` 询问用户以哪条为准，再删除或更新另一条
+- 仅在重要变更时更新记忆，保持简洁
+- 记忆过时或有误时，使用 \`更新\`（id + content）或 \`删除\`（id）操作，ID 可从 \`回忆\` 结果中的方括号获取
+- 记忆较多或杂乱时使用 \`整理\` 操作去重并调整分类；内容中带有 [过时] 标记的记忆会被归档
+- 需要确认用户之前做过的决定时，使用 \`历史\` 操作并在 content 中填写关键词检索之前的寸止交互`,
   } as PromptSection,
 
   // 代码搜索工具提示词
@@ -48,6 +55,18 @@ export const PROMPT_SECTIONS = {
     base: ``,
     detail: `代码搜索工具：如果需要查找/搜索代码，优先使用 \`sou\` 工具查询`,
   } as PromptSection,
+
+  // 代码审查工具提示词
+  shen: {
+    base: ``,
+    detail: `代码审查工具：需要用户确认代码改动时，使用 \`shen\` 工具提交 diff 或 git 修订范围，只保留用户接受的代码块并根据评论修改`,
+  } as PromptSection,
+
+  // 进度通知工具提示词
+  notify: {
+    base: ``,
+    detail: `进度通知工具：只需汇报进度、无需用户决策时，使用 \`notify\` 工具发送通知，它不会等待用户回复`,
+  } as PromptSection,
 }
 
 // 默认MCP工具配置
@@ -82,6 +101,26 @@ export const DEFAULT_MCP_TOOLS: MCPToolConfig[] = [
     iconBg: 'bg-green-100',
     darkIconBg: 'dark:bg-green-900',
   },
+  {
+    id: 'shen',
+    name: '代码审查工具',
+    description: '在弹窗中逐块审查代码改动，支持按文件、按代码块接受或拒绝并填写评论',
+    enabled: true,
+    canDisable: true,
+    icon: 'i-carbon-code-reference text-lg text-orange-600 dark:text-orange-400',
+    iconBg: 'bg-orange-100',
+    darkIconBg: 'dark:bg-orange-900',
+  },
+  {
+    id: 'notify',
+    name: '进度通知工具',
+    description: '向桌面和 Telegram 发送进度通知并播放提示音，不等待用户回复',
+    enabled: true,
+    canDisable: true,
+    icon: 'i-carbon-notification text-lg text-cyan-600 dark:text-cyan-400',
+    iconBg: 'bg-cyan-100',
+    darkIconBg: 'dark:bg-cyan-900',
+  },
 ]
 
 // 生成完整提示词（根据MCP工具开关状态）
@@ -4,6 +4,7 @@ import {
   NButton,
   NCard,
   NCheckbox,
+  NCheckboxGroup,
   NCode,
   NCollapse,
   NCollapseItem,
@@ -54,6 +55,7 @@ const naive = create({
     NButton,
     NCard,
     NCheckbox,
+    NCheckboxGroup,
     NCollapse,
     NCollapseItem,
     NCollapseTransition,
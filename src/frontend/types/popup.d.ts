@@ -3,8 +3,104 @@
 export interface McpRequest {
   id: string
   message: string
-  predefined_options?: string[]
+  predefined_options?: (string | PopupOption)[]
+  selection_mode?: SelectionMode
   is_markdown?: boolean
+  timeout_seconds?: number
+  default_option?: string
+  fields?: FormField[]
+  review?: DiffReview
+  client?: ClientIdentity
+  project_path?: string
+  questions?: PopupQuestion[]
+}
+
+// 批量提问中的单个问题
+export interface PopupQuestion {
+  id: string
+  message: string
+  predefined_options?: PopupOption[]
+  selection_mode?: SelectionMode
+}
+
+// 单个问题的回答，以问题ID为键
+export interface QuestionAnswer {
+  selected_options: string[]
+  user_input?: string
+}
+
+// 发起请求的客户端（来自 MCP initialize 握手）
+export interface ClientIdentity {
+  session_id: string
+  name?: string
+  version?: string
+}
+
+// 进度通知
+export type NotifyLevel = 'info' | 'success' | 'warning' | 'error'
+
+export interface NotifyRequest {
+  message: string
+  title?: string
+  level: NotifyLevel
+  is_markdown: boolean
+}
+
+// 代码审查
+export interface DiffHunk {
+  id: string
+  header: string
+  old_start: number
+  old_lines: number
+  new_start: number
+  new_lines: number
+  lines: string[]
+}
+
+export interface DiffFile {
+  path: string
+  old_path?: string
+  status: 'added' | 'deleted' | 'modified' | 'renamed'
+  binary?: boolean
+  hunks: DiffHunk[]
+}
+
+export interface DiffReview {
+  project_path: string
+  source: string
+  files: DiffFile[]
+}
+
+export interface ReviewVerdict {
+  hunks: { id: string, accepted: boolean, comment?: string }[]
+  files: { path: string, comment: string }[]
+  // 没有代码块的文件（二进制、纯重命名、仅权限变更）的整体审查结果
+  whole_files: { path: string, accepted: boolean }[]
+  comment?: string
+}
+
+// 预定义选项
+export interface PopupOption {
+  label: string
+  description?: string
+  value?: string
+  recommended?: boolean
+}
+
+// 选项的选择方式：单选、多选、仅展示
+export type SelectionMode = 'single' | 'multi' | 'none'
+
+// 结构化表单字段
+export type FormFieldType = 'text' | 'number' | 'boolean' | 'select' | 'multi_select' | 'file_path'
+
+export interface FormField {
+  name: string
+  label?: string
+  type: FormFieldType
+  description?: string
+  options?: string[]
+  default?: any
+  required?: boolean
 }
 
 // 自定义prompt类型定义
@@ -67,6 +163,9 @@ export interface McpResponse {
   user_input: string | null
   selected_options: string[]
   images: ImageAttachment[]
+  form_values?: Record<string, any>
+  review?: ReviewVerdict
+  answers?: Record<string, QuestionAnswer>
   metadata: ResponseMetadata
 }
 
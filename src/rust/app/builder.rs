@@ -1,4 +1,5 @@
 use crate::config::AppState;
+use crate::daemon::PopupQueue;
 use crate::ui::AudioController;
 use crate::app::{setup::setup_application, commands::*};
 use crate::log_important;
@@ -16,6 +17,7 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
         .manage(AudioController {
             should_stop: Arc::new(AtomicBool::new(false)),
         })
+        .manage(PopupQueue::default())
         .invoke_handler(tauri::generate_handler![
             // 基础应用命令
             get_app_info,
@@ -81,6 +83,14 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             crate::mcp::tools::acemcp::commands::debug_acemcp_search,
             crate::mcp::tools::acemcp::commands::execute_acemcp_tool,
 
+            // 交互历史命令
+            crate::mcp::tools::history::commands::get_interaction_history,
+            crate::mcp::tools::history::commands::get_interaction_history_projects,
+            crate::mcp::tools::history::commands::export_interaction_history,
+
+            // 记忆命令
+            crate::mcp::tools::memory::commands::organize_memory,
+
             // 自定义prompt命令
             get_custom_prompt_config,
             add_custom_prompt,
@@ -107,7 +117,6 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             set_telegram_config,
             test_telegram_connection_cmd,
             auto_get_chat_id,
-            start_telegram_sync,
 
             // 系统命令
             open_external_url,
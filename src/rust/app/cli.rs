@@ -18,6 +18,8 @@ pub fn handle_cli_args() -> Result<()> {
             match args[1].as_str() {
                 "--help" | "-h" => print_help(),
                 "--version" | "-v" => print_version(),
+                // 常驻模式：由 setup 阶段启动本地 socket 监听
+                "--daemon" => run_tauri_app(),
                 _ => {
                     eprintln!("未知参数: {}", args[1]);
                     print_help();
@@ -29,6 +31,9 @@ pub fn handle_cli_args() -> Result<()> {
         _ => {
             if args[1] == "--mcp-request" && args.len() >= 3 {
                 handle_mcp_request(&args[2])?;
+            } else if args[1] == "--notify" && args.len() >= 3 {
+                // 桌面通知：显示几秒后自动关闭
+                run_tauri_app();
             } else {
                 eprintln!("无效的命令行参数");
                 print_help();
@@ -75,6 +80,8 @@ fn print_help() {
     println!("用法:");
     println!("  等一下                    启动设置界面");
     println!("  等一下 --mcp-request <文件>  处理 MCP 请求");
+    println!("  等一下 --notify <文件>      显示桌面通知，几秒后自动关闭");
+    println!("  等一下 --daemon           常驻后台，在同一窗口中排队处理 MCP 请求");
     println!("  等一下 --help             显示此帮助信息");
     println!("  等一下 --version          显示版本信息");
 }
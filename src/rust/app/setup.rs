@@ -1,8 +1,11 @@
 use crate::config::{AppState, load_config_and_apply_window_settings};
+use crate::constants::mcp;
+use crate::daemon::{is_daemon_mode, start_daemon_listener};
 use crate::ui::{initialize_audio_asset_manager, setup_window_event_listeners};
 use crate::ui::exit_handler::setup_exit_handlers;
+use crate::mcp::types::PopupRequest;
 use crate::log_important;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, LogicalPosition, LogicalSize, Manager};
 
 /// 应用设置和初始化
 pub async fn setup_application(app_handle: &AppHandle) -> Result<(), String> {
@@ -26,5 +29,91 @@ pub async fn setup_application(app_handle: &AppHandle) -> Result<(), String> {
         log_important!(warn, "设置退出处理器失败: {}", e);
     }
 
+    // MCP 请求模式：为设置了超时的请求启动计时器
+    setup_mcp_request_timeout(app_handle);
+
+    // 桌面通知模式：缩小窗口并在显示一段时间后退出
+    setup_notify_window(app_handle);
+
+    // 常驻模式：隐藏窗口，等待 MCP 服务器通过本地 socket 发来请求
+    if is_daemon_mode() {
+        if let Some(window) = app_handle.get_webview_window("main") {
+            let _ = window.hide();
+        }
+        if let Err(e) = start_daemon_listener(app_handle) {
+            log_important!(error, "启动弹窗常驻进程失败: {}", e);
+            app_handle.exit(1);
+        }
+    }
+
     Ok(())
 }
+
+/// 请求等待超时后输出自动回复并退出
+fn setup_mcp_request_timeout(app_handle: &AppHandle) {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 3 || args[1] != "--mcp-request" {
+        return;
+    }
+
+    let request = match std::fs::read_to_string(&args[2])
+        .ok()
+        .and_then(|content| serde_json::from_str::<PopupRequest>(&content).ok())
+    {
+        Some(request) => request,
+        None => return,
+    };
+    let Some(timeout) = request.timeout() else { return };
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        log_important!(info, "MCP 请求等待超时，自动回复: {}", request.id);
+
+        // MCP模式：直接输出到stdout（MCP协议要求）
+        println!("{}", request.timeout_response());
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+        let _ = crate::ui::exit::force_exit_app(app_handle).await;
+    });
+}
+
+/// 通知窗口尺寸 (px)
+const NOTIFY_WINDOW_SIZE: (f64, f64) = (380.0, 140.0);
+
+/// 通知窗口与屏幕边缘的距离 (px)
+const NOTIFY_WINDOW_MARGIN: f64 = 16.0;
+
+/// 桌面通知模式：窗口缩小到屏幕右上角，到时自动退出
+fn setup_notify_window(app_handle: &AppHandle) {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 3 || args[1] != "--notify" {
+        return;
+    }
+
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let (width, height) = NOTIFY_WINDOW_SIZE;
+        let _ = window.set_min_size(None::<LogicalSize<f64>>);
+        let _ = window.set_max_size(None::<LogicalSize<f64>>);
+        let _ = window.set_size(LogicalSize::new(width, height));
+        let _ = window.set_decorations(false);
+        let _ = window.set_resizable(false);
+        let _ = window.set_always_on_top(true);
+        let _ = window.set_skip_taskbar(true);
+
+        if let Ok(Some(monitor)) = window.current_monitor() {
+            let scale = monitor.scale_factor();
+            let screen = monitor.size().to_logical::<f64>(scale);
+            let origin = monitor.position().to_logical::<f64>(scale);
+            let _ = window.set_position(LogicalPosition::new(
+                origin.x + screen.width - width - NOTIFY_WINDOW_MARGIN,
+                origin.y + NOTIFY_WINDOW_MARGIN,
+            ));
+        }
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(mcp::NOTIFY_DISPLAY_SECS)).await;
+        let _ = crate::ui::exit::force_exit_app(app_handle).await;
+    });
+}
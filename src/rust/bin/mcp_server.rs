@@ -1,11 +1,51 @@
 // MCP 服务器入口点
-use cunzhi::{mcp::run_server, utils::auto_init_logger, log_important};
+use cunzhi::{mcp::{run_server_with_transport, ServerTransport}, tui::run_tui_request_file, utils::auto_init_logger, log_important};
+use std::path::Path;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 自动初始化日志系统
     auto_init_logger()?;
 
-    log_important!(info, "启动 MCP 服务器");
-    run_server().await
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        print_help();
+        return Ok(());
+    }
+
+    // 终端弹窗子进程模式（由 tmux 窗格启动）
+    if args.first().map(String::as_str) == Some("--tui") {
+        let (Some(request_file), Some("--output"), Some(output_file)) =
+            (args.get(1), args.get(2).map(String::as_str), args.get(3))
+        else {
+            eprintln!("参数错误: --tui 需要 <请求文件> --output <响应文件>");
+            print_help();
+            std::process::exit(1);
+        };
+        run_tui_request_file(Path::new(request_file), Path::new(output_file))?;
+        return Ok(());
+    }
+
+    // 解析传输方式，默认使用 stdio
+    let transport = ServerTransport::from_args(&args).inspect_err(|e| {
+        eprintln!("参数错误: {}", e);
+        print_help();
+    })?;
+
+    log_important!(info, "启动 MCP 服务器，传输方式: {:?}", transport);
+    run_server_with_transport(transport).await
+}
+
+/// 显示帮助信息
+fn print_help() {
+    println!("寸止 MCP 服务器");
+    println!();
+    println!("用法:");
+    println!("  寸止                                       通过 stdio 提供服务（默认）");
+    println!("  寸止 --transport http [--bind 地址:端口] [--allow-remote]");
+    println!("                                             通过 Streamable HTTP 提供服务");
+    println!("  寸止 --tui <请求文件> --output <响应文件>   在当前终端中显示终端弹窗");
+    println!("  寸止 --help                                显示此帮助信息");
+    println!();
+    println!("HTTP 服务没有鉴权，默认只允许监听回环地址；监听其他地址需要加上 --allow-remote");
 }
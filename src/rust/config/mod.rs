@@ -1,5 +1,7 @@
 pub mod settings;
 pub mod storage;
+pub mod watcher;
 
 pub use settings::*;
 pub use storage::*;
+pub use watcher::*;
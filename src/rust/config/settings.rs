@@ -104,6 +104,53 @@ pub struct ReplyConfig {
     pub auto_continue_threshold: u32, // 字符数阈值
     #[serde(default = "default_continue_prompt")]
     pub continue_prompt: String, // 继续回复的提示词
+    #[serde(default)]
+    pub auto_answer_rules: Vec<AutoAnswerRule>, // 自动回复规则，按顺序匹配，第一条命中的规则生效
+}
+
+/// 自动回复规则
+///
+/// 寸止请求打开任何交互渠道之前按顺序匹配，命中后按 `action` 处理
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AutoAnswerRule {
+    #[serde(default)]
+    pub name: String, // 规则名称，用于日志
+    #[serde(default = "default_rule_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub target: RuleTarget, // 匹配的内容
+    #[serde(default)]
+    pub match_type: RuleMatchType,
+    pub pattern: String,
+    pub action: RuleAction,
+}
+
+/// 规则匹配的内容
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleTarget {
+    #[default]
+    Message, // 提问消息
+    Options, // 任一预定义选项的文本或值
+    ProjectPath, // 调用方项目路径
+}
+
+/// 规则匹配方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleMatchType {
+    #[default]
+    Substring, // 包含子串（不区分大小写）
+    Regex, // 正则表达式
+}
+
+/// 规则命中后的动作
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    SelectOption { option: String }, // 自动选择选项（按文本或值匹配）
+    Continue, // 自动回复继续提示词
+    Ask, // 总是询问，用于排除后面的自动回复规则
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -116,6 +163,37 @@ pub struct McpConfig {
     pub acemcp_max_lines_per_blob: Option<u32>, // acemcp最大行数/块
     pub acemcp_text_extensions: Option<Vec<String>>, // acemcp文件扩展名
     pub acemcp_exclude_patterns: Option<Vec<String>>, // acemcp排除模式
+    #[serde(default = "default_zhi_use_elicitation")]
+    pub zhi_use_elicitation: bool, // 客户端支持时通过MCP elicitation提问，而不是打开弹窗
+    #[serde(default = "default_zhi_tui_fallback")]
+    pub zhi_tui_fallback: bool, // 没有图形显示时使用终端弹窗
+    #[serde(default)]
+    pub zhi_tui_tmux_pane: Option<String>, // 终端弹窗所在的tmux窗格，未设置时使用控制终端
+    #[serde(default = "default_zhi_rate_limit")]
+    pub zhi_rate_limit: ZhiRateLimitConfig, // 寸止调用频率限制和循环检测
+    #[serde(default)]
+    pub zhi_script_file: Option<String>, // 脚本回答文件（YAML/JSON），设置后由脚本作答，用于自动化测试
+    #[serde(default)]
+    pub zhi_transcript_file: Option<String>, // 脚本模式下的请求记录文件，未设置时写在脚本旁边
+}
+
+/// 寸止调用频率限制
+///
+/// 限制触发时不再打开交互窗口，而是返回结构化错误或 `canned_reply`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ZhiRateLimitConfig {
+    #[serde(default = "default_rate_limit_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_rate_limit_window_secs")]
+    pub window_secs: u64, // 统计窗口（秒）
+    #[serde(default = "default_rate_limit_max_per_session")]
+    pub max_per_session: u32, // 每个客户端会话在窗口内的最大调用次数，0 表示不限制
+    #[serde(default = "default_rate_limit_max_per_project")]
+    pub max_per_project: u32, // 每个项目在窗口内的最大调用次数，0 表示不限制
+    #[serde(default = "default_rate_limit_max_repeats")]
+    pub max_repeats: u32, // 同一会话连续发送相同消息的最大次数，0 表示不检测
+    #[serde(default)]
+    pub canned_reply: Option<String>, // 限制触发时的固定回复，未设置时返回错误
 }
 
 // 自定义prompt结构
@@ -259,6 +337,12 @@ pub fn default_mcp_config() -> McpConfig {
         acemcp_max_lines_per_blob: None,
         acemcp_text_extensions: None,
         acemcp_exclude_patterns: None,
+        zhi_use_elicitation: default_zhi_use_elicitation(),
+        zhi_tui_fallback: default_zhi_tui_fallback(),
+        zhi_tui_tmux_pane: None,
+        zhi_rate_limit: default_zhi_rate_limit(),
+        zhi_script_file: None,
+        zhi_transcript_file: None,
     }
 }
 
@@ -316,6 +400,7 @@ pub fn default_reply_config() -> ReplyConfig {
         enable_continue_reply: mcp::DEFAULT_CONTINUE_REPLY_ENABLED,
         auto_continue_threshold: mcp::DEFAULT_AUTO_CONTINUE_THRESHOLD,
         continue_prompt: mcp::DEFAULT_CONTINUE_PROMPT.to_string(),
+        auto_answer_rules: Vec::new(),
     }
 }
 
@@ -347,6 +432,10 @@ pub fn default_auto_continue_threshold() -> u32 {
     mcp::DEFAULT_AUTO_CONTINUE_THRESHOLD
 }
 
+pub fn default_rule_enabled() -> bool {
+    true
+}
+
 pub fn default_continue_prompt() -> String {
     mcp::DEFAULT_CONTINUE_PROMPT.to_string()
 }
@@ -356,9 +445,50 @@ pub fn default_mcp_tools() -> HashMap<String, bool> {
     tools.insert(mcp::TOOL_ZHI.to_string(), true); // 寸止工具默认启用
     tools.insert(mcp::TOOL_JI.to_string(), false); // 记忆管理工具默认关闭
     tools.insert(mcp::TOOL_SOU.to_string(), false); // 代码搜索工具默认关闭
+    tools.insert(mcp::TOOL_SHEN.to_string(), true); // 代码审查工具默认启用
+    tools.insert(mcp::TOOL_NOTIFY.to_string(), true); // 进度通知工具默认启用
     tools
 }
 
+pub fn default_zhi_use_elicitation() -> bool {
+    mcp::DEFAULT_ZHI_USE_ELICITATION
+}
+
+pub fn default_zhi_tui_fallback() -> bool {
+    mcp::DEFAULT_ZHI_TUI_FALLBACK
+}
+
+pub fn default_zhi_rate_limit() -> ZhiRateLimitConfig {
+    ZhiRateLimitConfig {
+        enabled: default_rate_limit_enabled(),
+        window_secs: default_rate_limit_window_secs(),
+        max_per_session: default_rate_limit_max_per_session(),
+        max_per_project: default_rate_limit_max_per_project(),
+        max_repeats: default_rate_limit_max_repeats(),
+        canned_reply: None,
+    }
+}
+
+pub fn default_rate_limit_enabled() -> bool {
+    mcp::DEFAULT_RATE_LIMIT_ENABLED
+}
+
+pub fn default_rate_limit_window_secs() -> u64 {
+    mcp::DEFAULT_RATE_LIMIT_WINDOW_SECS
+}
+
+pub fn default_rate_limit_max_per_session() -> u32 {
+    mcp::DEFAULT_RATE_LIMIT_MAX_PER_SESSION
+}
+
+pub fn default_rate_limit_max_per_project() -> u32 {
+    mcp::DEFAULT_RATE_LIMIT_MAX_PER_PROJECT
+}
+
+pub fn default_rate_limit_max_repeats() -> u32 {
+    mcp::DEFAULT_RATE_LIMIT_MAX_REPEATS
+}
+
 pub fn default_window_width() -> f64 {
     window::DEFAULT_WIDTH
 }
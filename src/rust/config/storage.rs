@@ -144,7 +144,12 @@ pub fn load_standalone_telegram_config() -> Result<super::settings::TelegramConf
 }
 
 /// 获取独立配置文件路径（不依赖Tauri）
-fn get_standalone_config_path() -> Result<PathBuf> {
+pub fn get_standalone_config_path() -> Result<PathBuf> {
+    Ok(get_standalone_config_dir()?.join("config.json"))
+}
+
+/// 获取寸止配置目录（不依赖Tauri），其他本地数据文件也存放于此
+pub fn get_standalone_config_dir() -> Result<PathBuf> {
     // 使用标准的配置目录
     let config_dir = dirs::config_dir()
         .ok_or_else(|| anyhow::anyhow!("无法获取配置目录"))?
@@ -153,7 +158,7 @@ fn get_standalone_config_path() -> Result<PathBuf> {
     // 确保目录存在
     fs::create_dir_all(&config_dir)?;
 
-    Ok(config_dir.join("config.json"))
+    Ok(config_dir)
 }
 
 /// 合并默认快捷键配置，确保新的默认快捷键被添加到现有配置中
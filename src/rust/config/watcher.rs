@@ -0,0 +1,155 @@
+use anyhow::Result;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::sync::{Mutex, Once, OnceLock, RwLock};
+use tokio::sync::broadcast;
+
+use super::settings::AppConfig;
+use super::storage::{get_standalone_config_path, load_standalone_config};
+use crate::{log_debug, log_important};
+
+/// 配置变更事件
+#[derive(Debug, Clone)]
+pub struct ConfigChange {
+    /// MCP 工具启用状态是否变化
+    pub tools_changed: bool,
+    /// 自定义 prompt 是否变化
+    pub prompts_changed: bool,
+}
+
+/// 配置文件监听器
+///
+/// 在内存中保存最新的 `AppConfig`，配置文件被修改时重新加载并广播变更事件，
+/// 供 MCP 服务器等独立进程使用
+pub struct ConfigWatcher {
+    config: RwLock<AppConfig>,
+    changes: broadcast::Sender<ConfigChange>,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    watch_started: Once,
+}
+
+static CONFIG_WATCHER: OnceLock<ConfigWatcher> = OnceLock::new();
+
+/// 获取全局配置监听器，首次调用时加载配置并开始监听
+pub fn config_watcher() -> &'static ConfigWatcher {
+    let watcher = CONFIG_WATCHER.get_or_init(|| {
+        let config = load_standalone_config().unwrap_or_else(|e| {
+            log_important!(warn, "无法加载配置文件，使用默认配置: {}", e);
+            AppConfig::default()
+        });
+        let (changes, _) = broadcast::channel(16);
+
+        ConfigWatcher {
+            config: RwLock::new(config),
+            changes,
+            watcher: Mutex::new(None),
+            watch_started: Once::new(),
+        }
+    });
+
+    // 只尝试启动一次，失败后 `current` 退回到每次读取磁盘，不再重复告警
+    watcher.watch_started.call_once(|| watcher.start_once());
+    watcher
+}
+
+impl ConfigWatcher {
+    /// 获取当前配置的副本
+    pub fn current(&self) -> AppConfig {
+        if !self.is_watching() {
+            // 监听不可用时退回到每次读取磁盘
+            self.reload();
+        }
+
+        self.config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    /// 订阅配置变更事件
+    pub fn subscribe(&self) -> broadcast::Receiver<ConfigChange> {
+        self.changes.subscribe()
+    }
+
+    /// 重新从磁盘加载配置，有变化时广播事件
+    pub fn reload(&self) {
+        let new_config = match load_standalone_config() {
+            Ok(config) => config,
+            Err(e) => {
+                // 文件可能正在写入，保留旧配置等待下一次事件
+                log_debug!("重新加载配置失败，保留当前配置: {}", e);
+                return;
+            }
+        };
+
+        let change = {
+            let mut config = match self.config.write() {
+                Ok(config) => config,
+                Err(e) => {
+                    log_important!(warn, "获取配置写锁失败: {}", e);
+                    return;
+                }
+            };
+
+            let change = ConfigChange {
+                tools_changed: config.mcp_config.tools != new_config.mcp_config.tools,
+                prompts_changed: serde_json::to_value(&config.custom_prompt_config).ok()
+                    != serde_json::to_value(&new_config.custom_prompt_config).ok(),
+            };
+            *config = new_config;
+            change
+        };
+
+        if change.tools_changed || change.prompts_changed {
+            log_important!(info, "检测到配置变更: 工具={}, prompt={}", change.tools_changed, change.prompts_changed);
+            // 没有订阅者时发送失败是正常的
+            let _ = self.changes.send(change);
+        }
+    }
+
+    fn is_watching(&self) -> bool {
+        self.watcher
+            .lock()
+            .map(|watcher| watcher.is_some())
+            .unwrap_or(false)
+    }
+
+    fn start_once(&'static self) {
+        match self.start_watching() {
+            Ok(watcher) => {
+                if let Ok(mut guard) = self.watcher.lock() {
+                    *guard = Some(watcher);
+                }
+            }
+            Err(e) => log_important!(warn, "启动配置文件监听失败，将在每次使用时读取配置: {}", e),
+        }
+    }
+
+    fn start_watching(&'static self) -> Result<RecommendedWatcher> {
+        let config_path = get_standalone_config_path()?;
+        let config_dir = config_path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("无效的配置文件路径: {}", config_path.display()))?
+            .to_path_buf();
+        let file_name = config_path.file_name().map(|n| n.to_os_string());
+
+        // 监听目录而不是文件，兼容先删除再写入的保存方式
+        let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+            match result {
+                Ok(event) => {
+                    let touches_config = event
+                        .paths
+                        .iter()
+                        .any(|p| p.file_name().map(|n| n.to_os_string()) == file_name);
+                    if touches_config && (event.kind.is_modify() || event.kind.is_create()) {
+                        self.reload();
+                    }
+                }
+                Err(e) => log_debug!("配置文件监听错误: {}", e),
+            }
+        })?;
+        watcher.watch(&config_dir, RecursiveMode::NonRecursive)?;
+
+        log_debug!("开始监听配置文件: {}", config_path.display());
+        Ok(watcher)
+    }
+}
@@ -9,8 +9,14 @@ pub const TOOL_JI: &str = "ji";
 /// 代码搜索工具标识符
 pub const TOOL_SOU: &str = "sou";
 
+/// 代码审查工具标识符
+pub const TOOL_SHEN: &str = "shen";
+
+/// 进度通知工具标识符
+pub const TOOL_NOTIFY: &str = "notify";
+
 /// 默认启用的工具列表
-pub const DEFAULT_ENABLED_TOOLS: &[&str] = &[TOOL_ZHI, TOOL_JI, TOOL_SOU];
+pub const DEFAULT_ENABLED_TOOLS: &[&str] = &[TOOL_ZHI, TOOL_JI, TOOL_SOU, TOOL_SHEN, TOOL_NOTIFY];
 
 /// 继续回复默认启用状态
 pub const DEFAULT_CONTINUE_REPLY_ENABLED: bool = true;
@@ -21,12 +27,63 @@ pub const DEFAULT_AUTO_CONTINUE_THRESHOLD: u32 = 1000;
 /// 默认继续提示词
 pub const DEFAULT_CONTINUE_PROMPT: &str = "请按照最佳实践继续";
 
+/// 寸止默认不使用 MCP elicitation（需客户端支持并在配置中开启）
+pub const DEFAULT_ZHI_USE_ELICITATION: bool = false;
+
+/// 没有图形显示时默认使用终端弹窗
+pub const DEFAULT_ZHI_TUI_FALLBACK: bool = true;
+
+/// 寸止调用频率限制默认开启
+pub const DEFAULT_RATE_LIMIT_ENABLED: bool = true;
+
+/// 频率限制统计窗口 (秒)
+pub const DEFAULT_RATE_LIMIT_WINDOW_SECS: u64 = 60;
+
+/// 每个客户端会话在窗口内最多发起的寸止调用次数
+pub const DEFAULT_RATE_LIMIT_MAX_PER_SESSION: u32 = 10;
+
+/// 每个项目在窗口内最多发起的寸止调用次数
+pub const DEFAULT_RATE_LIMIT_MAX_PER_PROJECT: u32 = 20;
+
+/// 同一会话连续发送相同消息的最大次数
+pub const DEFAULT_RATE_LIMIT_MAX_REPEATS: u32 = 3;
+
+/// 脚本回答文件的环境变量，设置后寸止由脚本作答而不询问用户（用于自动化测试）
+pub const ZHI_SCRIPT_FILE_ENV: &str = "ZHI_SCRIPT_FILE";
+
+/// 脚本模式下请求记录文件的环境变量
+pub const ZHI_TRANSCRIPT_FILE_ENV: &str = "ZHI_TRANSCRIPT_FILE";
+
 /// MCP 请求超时时间 (ms)
 pub const REQUEST_TIMEOUT_MS: u64 = 30000;
 
 /// MCP 重试次数
 pub const MAX_RETRY_COUNT: u32 = 3;
 
+/// 桌面通知显示时长 (秒)，到时自动关闭
+pub const NOTIFY_DISPLAY_SECS: u64 = 6;
+
+/// 通知工具后台发送 Telegram 消息的最长时间 (秒)
+pub const NOTIFY_TELEGRAM_TIMEOUT_SECS: u64 = 10;
+
+/// 记忆回忆默认输出的最大字符数
+pub const DEFAULT_MEMORY_RECALL_MAX_CHARS: usize = 4000;
+
+/// HTTP 传输默认监听地址（仅本机）
+pub const DEFAULT_HTTP_BIND: &str = "127.0.0.1:8765";
+
+/// HTTP 传输的 MCP 端点路径
+pub const HTTP_ENDPOINT_PATH: &str = "/mcp";
+
+/// HTTP 传输 SSE 保活间隔 (秒)
+pub const HTTP_SSE_KEEP_ALIVE_SECS: u64 = 15;
+
+/// 弹窗常驻进程的 Unix socket 文件名（位于配置目录下）
+pub const DAEMON_SOCKET_FILE: &str = "popup-daemon.sock";
+
+/// 弹窗常驻进程的 Windows 命名管道名
+pub const DAEMON_PIPE_NAME: &str = "cunzhi-popup-daemon";
+
 // MCP 工具配置结构体
 #[derive(Debug, Clone)]
 pub struct McpToolConfig {
@@ -63,6 +120,8 @@ impl Default for McpConfig {
                 McpToolConfig::new(TOOL_ZHI, true, false), // 寸止工具不可禁用
                 McpToolConfig::new(TOOL_JI, false, true),   // 记忆管理工具可禁用，默认关闭
                 McpToolConfig::new(TOOL_SOU, false, true), // 代码搜索工具可禁用，默认关闭
+                McpToolConfig::new(TOOL_SHEN, true, true), // 代码审查工具可禁用，默认启用
+                McpToolConfig::new(TOOL_NOTIFY, true, true), // 进度通知工具可禁用，默认启用
             ],
             continue_reply_enabled: DEFAULT_CONTINUE_REPLY_ENABLED,
             auto_continue_threshold: DEFAULT_AUTO_CONTINUE_THRESHOLD,
@@ -124,5 +183,5 @@ pub fn get_default_mcp_config() -> McpConfig {
 
 /// 检查是否为有效的工具 ID
 pub fn is_valid_tool_id(tool_id: &str) -> bool {
-    matches!(tool_id, TOOL_ZHI | TOOL_JI | TOOL_SOU)
+    matches!(tool_id, TOOL_ZHI | TOOL_JI | TOOL_SOU | TOOL_SHEN | TOOL_NOTIFY)
 }
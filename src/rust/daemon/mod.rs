@@ -0,0 +1,15 @@
+//! 弹窗常驻进程
+//!
+//! `等一下 --daemon` 常驻后台并监听本地 socket（Windows 上为命名管道），
+//! MCP 服务器把弹窗请求发给它，在同一个窗口中排队展示，避免每次提问都启动新进程
+
+pub mod protocol;
+pub mod server;
+
+pub use protocol::{is_daemon_running, send_to_daemon};
+pub use server::{start_daemon_listener, PopupQueue};
+
+/// 当前进程是否以常驻模式启动
+pub fn is_daemon_mode() -> bool {
+    std::env::args().nth(1).as_deref() == Some("--daemon")
+}
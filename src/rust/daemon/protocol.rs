@@ -0,0 +1,82 @@
+use anyhow::Result;
+use interprocess::local_socket::{prelude::*, tokio::Stream as AsyncStream, Name, Stream};
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::constants::mcp;
+use crate::mcp::types::PopupRequest;
+use crate::log_debug;
+
+/// MCP 服务器发往常驻进程的消息，每条消息占一行 JSON
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DaemonRequest {
+    Popup { request: PopupRequest },
+}
+
+/// 常驻进程的回复，每条回复占一行 JSON
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DaemonReply {
+    Response { response: String },
+    Error { message: String },
+}
+
+/// 常驻进程监听的本地 socket 名
+///
+/// Unix 上是配置目录下的 socket 文件，Windows 上是命名管道
+pub fn daemon_socket_name() -> Result<Name<'static>> {
+    #[cfg(windows)]
+    {
+        use interprocess::local_socket::GenericNamespaced;
+        Ok(mcp::DAEMON_PIPE_NAME.to_ns_name::<GenericNamespaced>()?)
+    }
+
+    #[cfg(not(windows))]
+    {
+        use interprocess::local_socket::GenericFilePath;
+        let path = crate::config::get_standalone_config_dir()?.join(mcp::DAEMON_SOCKET_FILE);
+        Ok(path.to_fs_name::<GenericFilePath>()?)
+    }
+}
+
+/// 检查常驻进程是否在运行
+pub fn is_daemon_running() -> bool {
+    daemon_socket_name()
+        .and_then(|name| Ok(Stream::connect(name)?))
+        .is_ok()
+}
+
+/// 将弹窗请求交给常驻进程处理
+///
+/// 常驻进程未运行时返回 `Ok(None)`，由调用方回退到子进程弹窗；
+/// future 被丢弃时连接随之关闭，常驻进程会撤回该请求
+pub async fn send_to_daemon(request: &PopupRequest) -> Result<Option<String>> {
+    let stream = match AsyncStream::connect(daemon_socket_name()?).await {
+        Ok(stream) => stream,
+        Err(e) if matches!(e.kind(), ErrorKind::NotFound | ErrorKind::ConnectionRefused) => {
+            log_debug!("弹窗常驻进程未运行: {}", e);
+            return Ok(None);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let (recv, mut send) = stream.split();
+    let mut message = serde_json::to_string(&DaemonRequest::Popup { request: request.clone() })?;
+    message.push('\n');
+    send.write_all(message.as_bytes()).await?;
+    send.flush().await?;
+
+    // 等待用户在常驻窗口中作答
+    let mut recv = BufReader::new(recv);
+    let mut line = String::new();
+    if recv.read_line(&mut line).await? == 0 {
+        anyhow::bail!("弹窗常驻进程在回复前断开了连接");
+    }
+
+    match serde_json::from_str(line.trim())? {
+        DaemonReply::Response { response } => Ok(Some(response)),
+        DaemonReply::Error { message } => anyhow::bail!("弹窗常驻进程处理失败: {}", message),
+    }
+}
@@ -0,0 +1,202 @@
+use anyhow::Result;
+use interprocess::local_socket::{
+    tokio::{prelude::*, RecvHalf, SendHalf},
+    ListenerOptions,
+};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::oneshot;
+
+use super::protocol::{daemon_socket_name, is_daemon_running, DaemonReply, DaemonRequest};
+use crate::mcp::types::PopupRequest;
+use crate::{log_debug, log_important};
+
+/// 常驻模式下排队等待展示的弹窗请求
+///
+/// 同一时间只有一个请求显示在窗口中，作答后自动切换到下一个，队列清空时隐藏窗口
+#[derive(Default)]
+pub struct PopupQueue {
+    state: Mutex<QueueState>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    active: Option<PendingPopup>,
+    pending: VecDeque<PendingPopup>,
+}
+
+struct PendingPopup {
+    request: PopupRequest,
+    responder: oneshot::Sender<String>,
+}
+
+impl PopupQueue {
+    /// 加入队列，窗口空闲时立即展示
+    fn enqueue(&self, app: &AppHandle, request: PopupRequest, responder: oneshot::Sender<String>) {
+        let Ok(mut state) = self.state.lock() else { return };
+        state.pending.push_back(PendingPopup { request, responder });
+        log_debug!("弹窗请求入队，当前排队数: {}", state.pending.len());
+
+        if state.active.is_none() {
+            activate_next(&mut state, app);
+        }
+    }
+
+    /// 回复当前展示的请求并切换到下一个
+    ///
+    /// `request_id` 与当前请求不符时忽略，避免同一响应被重复提交到下一个请求
+    pub fn respond(&self, app: &AppHandle, request_id: Option<&str>, response: String) {
+        let Ok(mut state) = self.state.lock() else { return };
+
+        let matches = match (&state.active, request_id) {
+            (Some(active), Some(id)) => active.request.id == id,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if !matches {
+            log_debug!("忽略与当前弹窗不匹配的响应: {:?}", request_id);
+            return;
+        }
+
+        if let Some(active) = state.active.take() {
+            let _ = active.responder.send(response);
+        }
+        activate_next(&mut state, app);
+    }
+
+    /// 撤回请求（MCP 服务器已断开连接）
+    fn withdraw(&self, app: &AppHandle, request_id: &str) {
+        self.finish(app, request_id, None);
+    }
+
+    /// 请求等待超时，以自动回复结束
+    fn expire(&self, app: &AppHandle, request_id: &str, response: String) {
+        self.finish(app, request_id, Some(response));
+    }
+
+    /// 从队列中移除请求，无论是否正在展示
+    fn finish(&self, app: &AppHandle, request_id: &str, response: Option<String>) {
+        let Ok(mut state) = self.state.lock() else { return };
+
+        let finished = if state.active.as_ref().is_some_and(|a| a.request.id == request_id) {
+            let active = state.active.take();
+            let _ = app.emit("mcp-request-withdrawn", request_id);
+            activate_next(&mut state, app);
+            active
+        } else {
+            let index = state.pending.iter().position(|p| p.request.id == request_id);
+            index.and_then(|index| state.pending.remove(index))
+        };
+
+        if let (Some(finished), Some(response)) = (finished, response) {
+            let _ = finished.responder.send(response);
+        }
+    }
+}
+
+fn activate_next(state: &mut QueueState, app: &AppHandle) {
+    let Some(next) = state.pending.pop_front() else {
+        let _ = app.emit("mcp-queue-idle", ());
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.hide();
+        }
+        return;
+    };
+
+    if let Err(e) = app.emit("mcp-request", &next.request) {
+        log_important!(warn, "发送弹窗请求到前端失败: {}", e);
+    }
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+
+    state.active = Some(next);
+}
+
+/// 启动常驻模式的本地 socket 监听
+pub fn start_daemon_listener(app_handle: &AppHandle) -> Result<()> {
+    if is_daemon_running() {
+        anyhow::bail!("已有弹窗常驻进程在运行");
+    }
+
+    // 上一次异常退出可能留下 socket 文件，确认无人监听后覆盖
+    let listener = ListenerOptions::new()
+        .name(daemon_socket_name()?)
+        .try_overwrite(true)
+        .create_tokio()?;
+
+    log_important!(info, "弹窗常驻进程开始监听");
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok(conn) => {
+                    let app_handle = app_handle.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let (recv, send) = conn.split();
+                        if let Err(e) = handle_connection(app_handle, recv, send).await {
+                            log_important!(warn, "处理常驻弹窗连接失败: {}", e);
+                        }
+                    });
+                }
+                Err(e) => log_important!(warn, "接受常驻弹窗连接失败: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+async fn handle_connection(app: AppHandle, recv: RecvHalf, mut send: SendHalf) -> Result<()> {
+    let mut recv = BufReader::new(recv);
+    let mut line = String::new();
+    if recv.read_line(&mut line).await? == 0 {
+        return Ok(());
+    }
+
+    let reply = match serde_json::from_str::<DaemonRequest>(line.trim()) {
+        Ok(DaemonRequest::Popup { request }) => {
+            let request_id = request.id.clone();
+            let timeout = request.timeout();
+            let timeout_response = request.timeout_response();
+            let (tx, rx) = oneshot::channel();
+            app.state::<PopupQueue>().enqueue(&app, request, tx);
+
+            // 超时后以自动回复结束请求，结果仍通过 rx 返回
+            if let Some(timeout) = timeout {
+                let app = app.clone();
+                let request_id = request_id.clone();
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(timeout).await;
+                    app.state::<PopupQueue>().expire(&app, &request_id, timeout_response);
+                });
+            }
+
+            // 等待作答，同时留意 MCP 服务器是否已断开（如工具调用被取消）
+            let mut rest = String::new();
+            tokio::select! {
+                response = rx => match response {
+                    Ok(response) => DaemonReply::Response { response },
+                    Err(_) => DaemonReply::Error { message: "请求已被撤回".to_string() },
+                },
+                _ = recv.read_line(&mut rest) => {
+                    log_debug!("MCP 服务器已断开，撤回弹窗请求: {}", request_id);
+                    app.state::<PopupQueue>().withdraw(&app, &request_id);
+                    return Ok(());
+                }
+            }
+        }
+        Err(e) => DaemonReply::Error { message: format!("无效的请求: {}", e) },
+    };
+
+    let mut message = serde_json::to_string(&reply)?;
+    message.push('\n');
+    send.write_all(message.as_bytes()).await?;
+    send.flush().await?;
+    Ok(())
+}
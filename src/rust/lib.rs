@@ -1,8 +1,10 @@
 pub mod app;
 pub mod config;
 pub mod constants;
+pub mod daemon;
 pub mod mcp;
 pub mod telegram;
+pub mod tui;
 pub mod ui;
 pub mod utils;
 
@@ -0,0 +1,313 @@
+use anyhow::Result;
+use futures::future::BoxFuture;
+use rmcp::{
+    RoleServer,
+    model::{
+        CancelledNotificationParam, ClientResult, CreateElicitationRequest, CreateElicitationRequestParam,
+        CreateElicitationResult, ElicitationAction, RequestId, ServerRequest,
+    },
+    service::{Peer, PeerRequestOptions},
+};
+use std::sync::Mutex;
+
+use super::InteractionChannel;
+use crate::mcp::handlers::ask_each_question;
+use crate::mcp::types::{
+    build_continue_response, build_form_response, build_send_response, FormField, FormFieldType, PopupOption,
+    PopupRequest, SelectionMode,
+};
+use crate::log_debug;
+
+/// 自由文本输入字段名
+const USER_INPUT_FIELD: &str = "user_input";
+
+/// 单选字段名
+const CHOICE_FIELD: &str = "choice";
+
+/// 通过 MCP elicitation 在客户端中提问
+pub struct ElicitationChannel {
+    peer: Peer<RoleServer>,
+    /// 等待回答的 elicitation 请求ID，撤回时通知客户端取消
+    pending: Mutex<Option<RequestId>>,
+}
+
+impl ElicitationChannel {
+    pub fn new(peer: Peer<RoleServer>) -> Self {
+        Self {
+            peer,
+            pending: Mutex::new(None),
+        }
+    }
+}
+
+impl InteractionChannel for ElicitationChannel {
+    fn name(&self) -> &'static str {
+        "MCP 客户端"
+    }
+
+    fn ask<'a>(&'a self, request: &'a PopupRequest) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let Some(questions) = request.questions.as_deref() else {
+                return create_elicitation_popup(&self.peer, &self.pending, request).await;
+            };
+
+            // 批量提问：逐个发起 elicitation
+            ask_each_question(request, questions, "elicitation", |question_request| async move {
+                create_elicitation_popup(&self.peer, &self.pending, &question_request).await
+            })
+            .await
+        })
+    }
+
+    fn withdraw<'a>(&'a self, _request: &'a PopupRequest, answered_by: &'static str) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            cancel_pending(&self.peer, &self.pending, format!("已在{}回答", answered_by)).await;
+        })
+    }
+}
+
+/// 检查客户端是否在初始化时声明了 elicitation 能力
+pub fn client_supports_elicitation(peer: &Peer<RoleServer>) -> bool {
+    peer.peer_info()
+        .map(|info| info.capabilities.elicitation.is_some())
+        .unwrap_or(false)
+}
+
+/// 通过 MCP elicitation 向客户端提问
+///
+/// 预定义选项在多选模式下映射为布尔字段，单选模式下映射为枚举字段，自由文本映射为字符串字段，
+/// 表单字段映射为对应类型的字段，返回值与弹窗输出的 JSON 格式一致，可直接交给 `parse_mcp_response` 解析。
+/// 等待期间请求ID记录在 `pending` 中，超时或撤回时通知客户端取消
+pub async fn create_elicitation_popup(
+    peer: &Peer<RoleServer>,
+    pending: &Mutex<Option<RequestId>>,
+    request: &PopupRequest,
+) -> Result<String> {
+    let options = request.selectable_options().to_vec();
+    let single = request.selection_mode == SelectionMode::Single;
+
+    let mut properties = serde_json::Map::new();
+    if single && !options.is_empty() {
+        // 单选映射为枚举字段
+        properties.insert(CHOICE_FIELD.to_string(), serde_json::json!({
+            "type": "string",
+            "title": "请选择",
+            "enum": options.iter().map(|o| o.value.as_str()).collect::<Vec<_>>(),
+            "enumNames": options.iter().map(option_title).collect::<Vec<_>>()
+        }));
+    } else {
+        for (index, option) in options.iter().enumerate() {
+            let mut schema = serde_json::json!({
+                "type": "boolean",
+                "title": option_title(option),
+                "default": false
+            });
+            if let Some(description) = &option.description {
+                schema["description"] = description.clone().into();
+            }
+            properties.insert(option_field(index), schema);
+        }
+    }
+    let fields = request.fields.clone().unwrap_or_default();
+    let mut required = Vec::new();
+    for field in &fields {
+        properties.insert(form_field(field), form_field_schema(field));
+        if field.required {
+            required.push(form_field(field));
+        }
+    }
+    properties.insert(USER_INPUT_FIELD.to_string(), serde_json::json!({
+        "type": "string",
+        "title": "补充说明",
+        "description": "自由文本输入（可选）"
+    }));
+
+    let requested_schema = serde_json::from_value(serde_json::json!({
+        "type": "object",
+        "properties": properties,
+        "required": required
+    }))?;
+
+    // none 模式：选项仅作展示，附加在消息末尾
+    let message = match (request.selection_mode, &request.predefined_options) {
+        (SelectionMode::None, Some(options)) if !options.is_empty() => {
+            let list: Vec<String> = options
+                .iter()
+                .map(|option| match &option.description {
+                    Some(description) => format!("• {}：{}", option_title(option), description),
+                    None => format!("• {}", option_title(option)),
+                })
+                .collect();
+            format!("{}\n\n{}", request.message, list.join("\n"))
+        }
+        _ => request.message.clone(),
+    };
+
+    let elicitation = send_elicitation(
+        peer,
+        pending,
+        CreateElicitationRequestParam {
+            message,
+            requested_schema,
+        },
+    );
+
+    let result = match request.timeout() {
+        Some(timeout) => match tokio::time::timeout(timeout, elicitation).await {
+            Ok(result) => result,
+            Err(_) => {
+                log_debug!("elicitation 等待超时: {}", request.id);
+                cancel_pending(peer, pending, "等待超时".to_string()).await;
+                return Ok(request.timeout_response());
+            }
+        },
+        None => elicitation.await,
+    }?;
+
+    log_debug!("收到 elicitation 响应: {:?}", result.action);
+
+    let response = match result.action {
+        ElicitationAction::Accept => {
+            let content = result.content.unwrap_or_default();
+
+            let selected_options = if single {
+                content
+                    .get(CHOICE_FIELD)
+                    .and_then(|v| v.as_str())
+                    .map(|value| vec![value.to_string()])
+                    .unwrap_or_default()
+            } else {
+                options
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| {
+                        content.get(option_field(*index)).and_then(|v| v.as_bool()).unwrap_or(false)
+                    })
+                    .map(|(_, option)| option.value.clone())
+                    .collect()
+            };
+
+            let user_input = content
+                .get(USER_INPUT_FIELD)
+                .and_then(|v| v.as_str())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty());
+
+            if fields.is_empty() {
+                build_send_response(user_input, selected_options, vec![], Some(request.id.clone()), "elicitation")
+            } else {
+                // 类型转换和必填检查统一由 normalize_form_values 处理
+                let form_values = fields
+                    .iter()
+                    .filter_map(|field| {
+                        content.get(form_field(field)).map(|value| (field.name.clone(), value.clone()))
+                    })
+                    .collect();
+                build_form_response(form_values, user_input, selected_options, vec![], Some(request.id.clone()), "elicitation")
+            }
+        }
+        // 拒绝回答但允许继续，等同于点击继续
+        ElicitationAction::Decline => build_continue_response(Some(request.id.clone()), "elicitation_continue"),
+        ElicitationAction::Cancel => "CANCELLED".to_string(),
+    };
+
+    Ok(response)
+}
+
+/// 发起可取消的 elicitation 请求并等待结果
+async fn send_elicitation(
+    peer: &Peer<RoleServer>,
+    pending: &Mutex<Option<RequestId>>,
+    params: CreateElicitationRequestParam,
+) -> Result<CreateElicitationResult> {
+    let handle = peer
+        .send_cancellable_request(
+            ServerRequest::CreateElicitationRequest(CreateElicitationRequest::new(params)),
+            PeerRequestOptions::no_options(),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("elicitation 请求失败: {}", e))?;
+    if let Ok(mut id) = pending.lock() {
+        *id = Some(handle.id.clone());
+    }
+
+    let response = handle.await_response().await;
+    if let Ok(mut id) = pending.lock() {
+        *id = None;
+    }
+
+    match response.map_err(|e| anyhow::anyhow!("elicitation 请求失败: {}", e))? {
+        ClientResult::CreateElicitationResult(result) => Ok(result),
+        other => anyhow::bail!("elicitation 响应类型错误: {:?}", other),
+    }
+}
+
+/// 通知客户端取消仍在等待的 elicitation 请求，关闭其表单
+async fn cancel_pending(peer: &Peer<RoleServer>, pending: &Mutex<Option<RequestId>>, reason: String) {
+    let Some(request_id) = pending.lock().ok().and_then(|mut id| id.take()) else {
+        return;
+    };
+    if let Err(e) = peer
+        .notify_cancelled(CancelledNotificationParam {
+            request_id,
+            reason: Some(reason),
+        })
+        .await
+    {
+        log_debug!("取消 elicitation 请求失败: {}", e);
+    }
+}
+
+fn option_field(index: usize) -> String {
+    format!("option_{}", index + 1)
+}
+
+fn option_title(option: &PopupOption) -> String {
+    if option.recommended {
+        format!("{}（推荐）", option.label)
+    } else {
+        option.label.clone()
+    }
+}
+
+fn form_field(field: &FormField) -> String {
+    format!("field_{}", field.name)
+}
+
+/// elicitation 只支持基本类型，多选以逗号分隔的字符串表示
+fn form_field_schema(field: &FormField) -> serde_json::Value {
+    let mut schema = match field.field_type {
+        FormFieldType::Number => serde_json::json!({ "type": "number" }),
+        FormFieldType::Boolean => serde_json::json!({ "type": "boolean" }),
+        FormFieldType::Select if !field.options.is_empty() => serde_json::json!({
+            "type": "string",
+            "enum": field.options
+        }),
+        FormFieldType::MultiSelect if !field.options.is_empty() => serde_json::json!({
+            "type": "string",
+            "description": format!("可多选，用逗号分隔：{}", field.options.join(", "))
+        }),
+        _ => serde_json::json!({ "type": "string" }),
+    };
+
+    schema["title"] = field.display_label().into();
+    if let Some(description) = &field.description {
+        let description = match schema.get("description").and_then(|d| d.as_str()) {
+            Some(hint) => format!("{}（{}）", description, hint),
+            None => description.clone(),
+        };
+        schema["description"] = description.into();
+    }
+    if let Some(default) = &field.default {
+        schema["default"] = match (field.field_type, default) {
+            (FormFieldType::MultiSelect, serde_json::Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+                .into(),
+            _ => default.clone(),
+        };
+    }
+    schema
+}
@@ -0,0 +1,132 @@
+//! 交互渠道
+//!
+//! 寸止请求可以同时发往多个渠道（桌面弹窗、终端、Telegram、MCP elicitation），
+//! 第一个作答的渠道胜出，其余渠道的请求会被撤回
+
+pub mod elicitation;
+pub mod popup;
+pub mod scripted;
+pub mod telegram;
+pub mod terminal;
+
+use anyhow::Result;
+use futures::future::{join_all, BoxFuture};
+use futures::stream::{FuturesUnordered, StreamExt};
+use rmcp::{RoleServer, service::Peer};
+
+use crate::config::config_watcher;
+use crate::mcp::types::PopupRequest;
+use crate::tui::should_use_tui;
+use crate::{log_debug, log_important};
+
+pub use elicitation::ElicitationChannel;
+pub use popup::PopupChannel;
+pub use scripted::ScriptedChannel;
+pub use telegram::TelegramChannel;
+pub use terminal::TerminalChannel;
+
+/// 交互渠道
+///
+/// `ask` 返回的 future 被丢弃时应关闭该渠道上的界面；
+/// `withdraw` 用于在界面之外留下痕迹（如编辑已发送的 Telegram 消息）
+pub trait InteractionChannel: Send + Sync {
+    /// 渠道名称，用于日志和撤回提示
+    fn name(&self) -> &'static str;
+
+    /// 向用户提问，返回与弹窗相同格式的响应
+    fn ask<'a>(&'a self, request: &'a PopupRequest) -> BoxFuture<'a, Result<String>>;
+
+    /// 其他渠道已作答时撤回请求
+    fn withdraw<'a>(&'a self, _request: &'a PopupRequest, _answered_by: &'static str) -> BoxFuture<'a, ()> {
+        Box::pin(async {})
+    }
+}
+
+/// 根据配置和客户端能力选择本次请求使用的渠道
+///
+/// - 启用 elicitation 且客户端支持时通过 MCP 客户端提问，否则使用本机的桌面弹窗或终端弹窗
+/// - 纯 Telegram 模式下不使用本机弹窗
+/// - Telegram 启用时同时发往 Telegram
+/// - 指定了脚本文件时只由脚本作答（见 `ScriptedChannel`）
+pub fn select_channels(peer: Option<&Peer<RoleServer>>) -> Vec<Box<dyn InteractionChannel>> {
+    let config = config_watcher().current();
+    let mut channels: Vec<Box<dyn InteractionChannel>> = Vec::new();
+
+    if let Some(channel) = ScriptedChannel::from_config(&config.mcp_config) {
+        channels.push(Box::new(channel));
+        return channels;
+    }
+
+    let telegram_only = config.telegram_config.enabled && config.telegram_config.hide_frontend_popup;
+    let elicitation_peer = peer.filter(|peer| {
+        config.mcp_config.zhi_use_elicitation && elicitation::client_supports_elicitation(peer)
+    });
+
+    if let Some(peer) = elicitation_peer {
+        channels.push(Box::new(ElicitationChannel::new(peer.clone())));
+    } else if !telegram_only {
+        if should_use_tui() {
+            channels.push(Box::new(TerminalChannel));
+        } else {
+            channels.push(Box::new(PopupChannel));
+        }
+    }
+
+    match TelegramChannel::from_config(&config.telegram_config) {
+        Ok(Some(channel)) => channels.push(Box::new(channel)),
+        Ok(None) => {}
+        Err(e) => log_important!(warn, "创建Telegram渠道失败: {}", e),
+    }
+
+    channels
+}
+
+/// 同时向所有渠道提问，返回第一个作答的响应并撤回其余渠道的请求
+///
+/// 单个渠道失败不影响其他渠道，全部失败时返回汇总错误
+pub async fn ask_first(channels: &[Box<dyn InteractionChannel>], request: &PopupRequest) -> Result<String> {
+    if channels.is_empty() {
+        anyhow::bail!("没有可用的交互渠道");
+    }
+
+    let mut pending: FuturesUnordered<_> = channels
+        .iter()
+        .enumerate()
+        .map(|(index, channel)| async move { (index, channel.ask(request).await) })
+        .collect();
+
+    let mut errors = Vec::new();
+    let mut answer = None;
+    while let Some((index, result)) = pending.next().await {
+        match result {
+            Ok(response) => {
+                answer = Some((index, response));
+                break;
+            }
+            Err(e) => {
+                log_important!(warn, "交互渠道 {} 失败: {}", channels[index].name(), e);
+                errors.push(format!("{}: {}", channels[index].name(), e));
+            }
+        }
+    }
+    // 丢弃其余渠道的等待，关闭它们的界面
+    drop(pending);
+
+    let Some((winner, response)) = answer else {
+        anyhow::bail!("所有交互渠道均失败: {}", errors.join("; "));
+    };
+
+    let answered_by = channels[winner].name();
+    log_debug!("请求 {} 已在 {} 作答", request.id, answered_by);
+
+    join_all(
+        channels
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != winner)
+            .map(|(_, channel)| channel.withdraw(request, answered_by)),
+    )
+    .await;
+
+    Ok(response)
+}
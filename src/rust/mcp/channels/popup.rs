@@ -0,0 +1,19 @@
+use anyhow::Result;
+use futures::future::BoxFuture;
+
+use super::InteractionChannel;
+use crate::mcp::handlers::create_tauri_popup;
+use crate::mcp::types::PopupRequest;
+
+/// 桌面弹窗（常驻进程或独立的等一下进程）
+pub struct PopupChannel;
+
+impl InteractionChannel for PopupChannel {
+    fn name(&self) -> &'static str {
+        "桌面弹窗"
+    }
+
+    fn ask<'a>(&'a self, request: &'a PopupRequest) -> BoxFuture<'a, Result<String>> {
+        Box::pin(create_tauri_popup(request))
+    }
+}
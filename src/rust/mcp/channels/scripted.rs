@@ -0,0 +1,279 @@
+//! 脚本作答渠道
+//!
+//! 用于在 CI 中测试代理工作流：寸止请求由脚本文件中的预设回答作答，不询问用户，
+//! 每个请求（包括没有匹配回答的请求）都追加到记录文件中
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use super::InteractionChannel;
+use crate::config::McpConfig;
+use crate::constants::mcp;
+use crate::mcp::types::{build_batch_response, build_send_response, PopupRequest, QuestionAnswer};
+use crate::log_important;
+
+/// 脚本回答的来源标识
+pub const SCRIPT_SOURCE: &str = "script";
+
+/// 脚本文件内容
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Script {
+    #[serde(default)]
+    pub answers: Vec<ScriptedAnswer>,
+}
+
+/// 一条预设回答
+///
+/// 按顺序查找第一条可用且匹配的回答；`call` 和 `message` 都未设置时匹配任意请求
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ScriptedAnswer {
+    /// 按调用顺序匹配（从 1 开始）
+    #[serde(default)]
+    pub call: Option<usize>,
+    /// 按消息正则匹配
+    #[serde(default)]
+    pub message: Option<String>,
+    /// 可重复使用，默认每条回答只用一次
+    #[serde(default)]
+    pub repeat: bool,
+    #[serde(default)]
+    pub selected_options: Vec<String>,
+    #[serde(default)]
+    pub user_input: Option<String>,
+    /// 批量提问的回答，以问题ID为键
+    #[serde(default)]
+    pub answers: Option<BTreeMap<String, QuestionAnswer>>,
+    /// 模拟用户取消
+    #[serde(default)]
+    pub cancel: bool,
+}
+
+impl ScriptedAnswer {
+    fn response(&self, request: &PopupRequest) -> String {
+        if self.cancel {
+            return "CANCELLED".to_string();
+        }
+
+        match (&self.answers, &request.questions) {
+            (Some(answers), Some(_)) => build_batch_response(
+                answers.clone(),
+                self.user_input.clone(),
+                vec![],
+                Some(request.id.clone()),
+                SCRIPT_SOURCE,
+            ),
+            _ => build_send_response(
+                self.user_input.clone(),
+                self.selected_options.clone(),
+                vec![],
+                Some(request.id.clone()),
+                SCRIPT_SOURCE,
+            ),
+        }
+    }
+}
+
+/// 已加载的脚本和作答进度
+struct ScriptState {
+    path: PathBuf,
+    answers: Vec<(ScriptedAnswer, Option<Regex>)>,
+    used: Vec<bool>,
+    calls: usize,
+}
+
+impl ScriptState {
+    fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("读取脚本文件失败: {}", path.display()))?;
+        let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        Self::parse(path, &content, is_json)
+    }
+
+    fn parse(path: &Path, content: &str, is_json: bool) -> Result<Self> {
+        let script: Script = if is_json {
+            serde_json::from_str(content)?
+        } else {
+            serde_yaml::from_str(content)?
+        };
+
+        let answers = script
+            .answers
+            .into_iter()
+            .map(|answer| {
+                let regex = answer
+                    .message
+                    .as_deref()
+                    .map(Regex::new)
+                    .transpose()
+                    .with_context(|| format!("脚本中的消息正则无效: {:?}", answer.message))?;
+                Ok((answer, regex))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            used: vec![false; answers.len()],
+            answers,
+            calls: 0,
+        })
+    }
+
+    /// 记录一次调用并查找匹配的回答，返回调用序号和回答下标
+    fn next(&mut self, message: &str) -> (usize, Option<usize>) {
+        self.calls += 1;
+        let call = self.calls;
+
+        let matched = (0..self.answers.len()).find(|&index| {
+            let (answer, regex) = &self.answers[index];
+            (answer.repeat || !self.used[index])
+                && answer.call.is_none_or(|expected| expected == call)
+                && regex.as_ref().is_none_or(|regex| regex.is_match(message))
+        });
+        if let Some(index) = matched {
+            self.used[index] = true;
+        }
+        (call, matched)
+    }
+}
+
+/// 请求记录，每行一条
+#[derive(Serialize)]
+struct TranscriptEntry<'a> {
+    call: usize,
+    at: DateTime<Utc>,
+    request: &'a PopupRequest,
+    /// 匹配的回答在脚本中的下标
+    matched: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// 脚本作答，不打开任何界面
+pub struct ScriptedChannel {
+    script: PathBuf,
+    transcript: PathBuf,
+}
+
+impl ScriptedChannel {
+    /// 环境变量或配置中指定了脚本文件时创建渠道，环境变量优先
+    pub fn from_config(config: &McpConfig) -> Option<Self> {
+        let script = script_file(config)?;
+        let transcript = env_path(mcp::ZHI_TRANSCRIPT_FILE_ENV)
+            .or_else(|| config.zhi_transcript_file.as_deref().filter(|p| !p.trim().is_empty()).map(PathBuf::from))
+            .unwrap_or_else(|| script.with_extension("transcript.jsonl"));
+        Some(Self { script, transcript })
+    }
+
+    fn answer(&self, request: &PopupRequest) -> Result<String> {
+        let mut state = script_state().lock().map_err(|_| anyhow::anyhow!("脚本状态不可用"))?;
+        // 首次使用或脚本文件变更时重新加载
+        if state.as_ref().is_none_or(|state| state.path != self.script) {
+            *state = Some(ScriptState::load(&self.script)?);
+        }
+        let Some(state) = state.as_mut() else {
+            anyhow::bail!("脚本未加载");
+        };
+
+        let (call, matched) = state.next(&request.message);
+        let result = match matched {
+            Some(index) => Ok(state.answers[index].0.response(request)),
+            None => Err(anyhow::anyhow!(
+                "脚本 {} 中没有匹配第 {} 次调用的回答，消息: {}",
+                self.script.display(),
+                call,
+                request.message
+            )),
+        };
+
+        self.record(TranscriptEntry {
+            call,
+            at: Utc::now(),
+            request,
+            matched,
+            response: result.as_ref().ok().map(|response| {
+                serde_json::from_str(response).unwrap_or_else(|_| serde_json::Value::String(response.clone()))
+            }),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+
+        if let Err(e) = &result {
+            log_important!(error, "{}", e);
+        }
+        result
+    }
+
+    fn record(&self, entry: TranscriptEntry) {
+        let written = serde_json::to_string(&entry).map_err(anyhow::Error::from).and_then(|mut line| {
+            line.push('\n');
+            let mut file = OpenOptions::new().create(true).append(true).open(&self.transcript)?;
+            file.write_all(line.as_bytes())?;
+            Ok(())
+        });
+        if let Err(e) = written {
+            log_important!(warn, "写入脚本记录文件 {} 失败: {}", self.transcript.display(), e);
+        }
+    }
+}
+
+impl InteractionChannel for ScriptedChannel {
+    fn name(&self) -> &'static str {
+        "脚本"
+    }
+
+    fn ask<'a>(&'a self, request: &'a PopupRequest) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move { self.answer(request) })
+    }
+}
+
+/// 当前使用的脚本文件，未启用脚本模式时返回 `None`
+pub fn script_file(config: &McpConfig) -> Option<PathBuf> {
+    env_path(mcp::ZHI_SCRIPT_FILE_ENV)
+        .or_else(|| config.zhi_script_file.as_deref().filter(|p| !p.trim().is_empty()).map(PathBuf::from))
+}
+
+fn env_path(var: &str) -> Option<PathBuf> {
+    std::env::var(var).ok().filter(|value| !value.trim().is_empty()).map(PathBuf::from)
+}
+
+/// 进程内共享的作答进度，调用序号跨请求累计
+fn script_state() -> &'static Mutex<Option<ScriptState>> {
+    static STATE: OnceLock<Mutex<Option<ScriptState>>> = OnceLock::new();
+    STATE.get_or_init(|| Mutex::new(None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = r#"
+answers:
+  - call: 2
+    user_input: 第二次调用
+  - message: "^继续"
+    repeat: true
+    selected_options: [是]
+  - user_input: 兜底回答
+"#;
+
+    #[test]
+    fn test_matches_by_order_and_message() {
+        let mut state = ScriptState::parse(Path::new("script.yaml"), SCRIPT, false).unwrap();
+
+        assert_eq!(state.next("继续下一步？"), (1, Some(1)));
+        assert_eq!(state.next("继续下一步？"), (2, Some(0)));
+        assert_eq!(state.next("继续吗？"), (3, Some(1)));
+        assert_eq!(state.next("部署到哪里？"), (4, Some(2)));
+        // 一次性回答已用完
+        assert_eq!(state.next("部署到哪里？"), (5, None));
+    }
+}
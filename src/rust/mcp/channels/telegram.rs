@@ -0,0 +1,59 @@
+use anyhow::Result;
+use futures::future::BoxFuture;
+use std::sync::Mutex;
+
+use super::InteractionChannel;
+use crate::config::TelegramConfig;
+use crate::mcp::types::PopupRequest;
+use crate::telegram::{
+    send_telegram_request, telegram_core_from_config, wait_for_telegram_answer,
+    withdraw_telegram_request, TelegramCore,
+};
+
+/// Telegram 机器人
+pub struct TelegramChannel {
+    core: TelegramCore,
+    /// 最近发送的选项消息ID（批量提问时为当前问题），撤回时用于编辑该消息；尚未发送时为 `None`
+    options_message_id: Mutex<Option<Option<i32>>>,
+}
+
+impl TelegramChannel {
+    /// 根据配置创建渠道，未启用或配置不完整时返回 `None`
+    pub fn from_config(config: &TelegramConfig) -> Result<Option<Self>> {
+        Ok(telegram_core_from_config(config)?.map(|core| Self {
+            core,
+            options_message_id: Mutex::new(None),
+        }))
+    }
+
+    fn record_message_id(&self, message_id: Option<i32>) {
+        if let Ok(mut id) = self.options_message_id.lock() {
+            *id = Some(message_id);
+        }
+    }
+}
+
+impl InteractionChannel for TelegramChannel {
+    fn name(&self) -> &'static str {
+        "Telegram"
+    }
+
+    fn ask<'a>(&'a self, request: &'a PopupRequest) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let message_id = send_telegram_request(&self.core, request).await?;
+            self.record_message_id(message_id);
+            // 批量提问时记录当前问题的消息，撤回时编辑其按钮
+            wait_for_telegram_answer(&self.core, request, |message_id| self.record_message_id(message_id)).await
+        })
+    }
+
+    fn withdraw<'a>(&'a self, _request: &'a PopupRequest, answered_by: &'static str) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            // 消息还没发出去时无需撤回
+            let Some(message_id) = self.options_message_id.lock().ok().and_then(|id| *id) else {
+                return;
+            };
+            withdraw_telegram_request(&self.core, message_id, answered_by).await;
+        })
+    }
+}
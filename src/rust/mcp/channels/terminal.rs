@@ -0,0 +1,35 @@
+use anyhow::Result;
+use futures::future::BoxFuture;
+use tokio_util::sync::CancellationToken;
+
+use super::InteractionChannel;
+use crate::mcp::handlers::ask_each_question;
+use crate::mcp::types::PopupRequest;
+use crate::tui::create_tui_popup;
+
+/// 终端弹窗（控制终端或 tmux 窗格）
+pub struct TerminalChannel;
+
+impl InteractionChannel for TerminalChannel {
+    fn name(&self) -> &'static str {
+        "终端"
+    }
+
+    fn ask<'a>(&'a self, request: &'a PopupRequest) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            // 终端弹窗在阻塞线程中运行，future 被丢弃时通过取消令牌通知它退出
+            let cancel = CancellationToken::new();
+            let _guard = cancel.clone().drop_guard();
+            let ask = |request: PopupRequest| {
+                let cancel = cancel.clone();
+                async move { tokio::task::spawn_blocking(move || create_tui_popup(&request, &cancel)).await? }
+            };
+
+            match request.questions.as_deref() {
+                // 批量提问：逐个打开终端弹窗
+                Some(questions) => ask_each_question(request, questions, "tui", ask).await,
+                None => ask(request.clone()).await,
+            }
+        })
+    }
+}
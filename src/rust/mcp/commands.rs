@@ -66,6 +66,32 @@ pub async fn get_mcp_tools_config(state: State<'_, AppState>) -> Result<Vec<MCPT
         has_config: true, // 代码搜索工具有配置选项
     });
     
+    // 代码审查工具 - 始终存在，无配置选项
+    tools.push(MCPToolConfig {
+        id: mcp::TOOL_SHEN.to_string(),
+        name: "代码审查".to_string(),
+        description: "在弹窗中逐块审查代码改动，支持按文件、按代码块接受或拒绝并填写评论".to_string(),
+        enabled: config.mcp_config.tools.get(mcp::TOOL_SHEN).copied().unwrap_or(true),
+        can_disable: true,
+        icon: "i-carbon-code-reference text-lg text-orange-600 dark:text-orange-400".to_string(),
+        icon_bg: "bg-orange-100 dark:bg-orange-900".to_string(),
+        dark_icon_bg: "dark:bg-orange-800".to_string(),
+        has_config: false, // 代码审查工具没有配置选项
+    });
+
+    // 进度通知工具 - 始终存在，无配置选项
+    tools.push(MCPToolConfig {
+        id: mcp::TOOL_NOTIFY.to_string(),
+        name: "进度通知".to_string(),
+        description: "向桌面和 Telegram 发送进度通知并播放提示音，不等待用户回复".to_string(),
+        enabled: config.mcp_config.tools.get(mcp::TOOL_NOTIFY).copied().unwrap_or(true),
+        can_disable: true,
+        icon: "i-carbon-notification text-lg text-cyan-600 dark:text-cyan-400".to_string(),
+        icon_bg: "bg-cyan-100 dark:bg-cyan-900".to_string(),
+        dark_icon_bg: "dark:bg-cyan-800".to_string(),
+        has_config: false, // 进度通知工具没有配置选项
+    });
+
     // 按启用状态排序，启用的在前
     tools.sort_by(|a, b| b.enabled.cmp(&a.enabled));
     
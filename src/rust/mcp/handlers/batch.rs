@@ -0,0 +1,141 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::Instant;
+
+use crate::mcp::types::{build_batch_response, PopupQuestion, PopupRequest, QuestionAnswer};
+
+/// 批量提问：逐个提问并合并为一个响应，用于一次只能展示一个问题的渠道
+///
+/// `ask` 接收单个问题的请求（见 `PopupQuestion::as_request`）；任一问题被取消时整体取消。
+/// 请求的超时时间由所有问题共用：每个问题只能使用剩余的时间，截止后其余问题直接使用自动回复
+pub async fn ask_each_question<F, Fut>(
+    request: &PopupRequest,
+    questions: &[PopupQuestion],
+    source: &str,
+    mut ask: F,
+) -> Result<String>
+where
+    F: FnMut(PopupRequest) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    let deadline = request.timeout().map(|timeout| Instant::now() + timeout);
+    let mut answers = BTreeMap::new();
+    for (index, question) in questions.iter().enumerate() {
+        let mut question_request = question.as_request(request, index, questions.len());
+        let response = match deadline.map(|deadline| deadline.saturating_duration_since(Instant::now())) {
+            Some(remaining) if remaining.is_zero() => question_request.timeout_response(),
+            remaining => {
+                // 按整秒向上取整，避免剩余不足一秒时变为不限时
+                question_request.timeout_seconds = remaining
+                    .map(|remaining| remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0))
+                    .or(question_request.timeout_seconds);
+                ask(question_request).await?
+            }
+        };
+        let Some(answer) = QuestionAnswer::from_response(&response) else {
+            return Ok("CANCELLED".to_string());
+        };
+        answers.insert(question.id.clone(), answer);
+    }
+
+    Ok(build_batch_response(answers, None, vec![], Some(request.id.clone()), source))
+}
+
+/// 按问题列表整理批量回答：每个问题都有一项，未知的问题ID被丢弃
+///
+/// 各渠道提交的选项可能是文本（如 Telegram），这里统一转换为选项的值
+pub fn normalize_batch_answers(
+    questions: &[PopupQuestion],
+    submitted: Option<&BTreeMap<String, QuestionAnswer>>,
+) -> BTreeMap<String, QuestionAnswer> {
+    questions
+        .iter()
+        .map(|question| {
+            let mut answer = submitted
+                .and_then(|answers| answers.get(&question.id))
+                .cloned()
+                .unwrap_or_default();
+            answer.selected_options = answer
+                .selected_options
+                .into_iter()
+                .map(|choice| {
+                    question
+                        .predefined_options
+                        .iter()
+                        .find(|option| option.label == choice || option.value == choice)
+                        .map(|option| option.value.clone())
+                        .unwrap_or(choice)
+                })
+                .collect();
+            (question.id.clone(), answer)
+        })
+        .collect()
+}
+
+/// 生成批量回答的文本摘要，按提问顺序列出
+pub fn format_batch_summary(questions: &[PopupQuestion], answers: &BTreeMap<String, QuestionAnswer>) -> String {
+    let mut lines = vec!["批量回答:".to_string()];
+    for question in questions {
+        let title = question.message.lines().next().unwrap_or_default().trim();
+        lines.push(format!("- [{}] {}", question.id, title));
+
+        match answers.get(&question.id).filter(|answer| !answer.is_empty()) {
+            Some(answer) => {
+                if !answer.selected_options.is_empty() {
+                    lines.push(format!("  选择: {}", answer.selected_options.join(", ")));
+                }
+                if let Some(input) = &answer.user_input {
+                    lines.push(format!("  回答: {}", input));
+                }
+            }
+            None => lines.push("  （未回答）".to_string()),
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::types::PopupOption;
+
+    fn questions() -> Vec<PopupQuestion> {
+        vec![
+            PopupQuestion {
+                id: "db".to_string(),
+                message: "使用哪个数据库？".to_string(),
+                predefined_options: vec![PopupOption {
+                    label: "PostgreSQL".to_string(),
+                    description: None,
+                    value: "pg".to_string(),
+                    recommended: false,
+                }],
+                selection_mode: Default::default(),
+            },
+            PopupQuestion {
+                id: "name".to_string(),
+                message: "项目名称？".to_string(),
+                predefined_options: Vec::new(),
+                selection_mode: Default::default(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_normalize_batch_answers() {
+        let submitted = BTreeMap::from([
+            (
+                "db".to_string(),
+                QuestionAnswer { selected_options: vec!["PostgreSQL".to_string()], user_input: None },
+            ),
+            ("unknown".to_string(), QuestionAnswer::default()),
+        ]);
+
+        let answers = normalize_batch_answers(&questions(), Some(&submitted));
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers["db"].selected_options, vec!["pg".to_string()]);
+        assert!(answers["name"].is_empty());
+        assert!(format_batch_summary(&questions(), &answers).contains("[name] 项目名称？\n  （未回答）"));
+    }
+}
@@ -0,0 +1,227 @@
+use serde_json::{Map, Value};
+
+use crate::mcp::types::{FormField, FormFieldType};
+
+/// 整理后的表单结果
+#[derive(Debug, Default, PartialEq)]
+pub struct FormValues {
+    /// 按字段类型转换后的值，未填写的可选字段为 null
+    pub values: Map<String, Value>,
+    /// 未填写的必填字段
+    pub missing: Vec<String>,
+    /// 无法按字段类型解析的字段
+    pub invalid: Vec<String>,
+}
+
+/// 按字段定义整理用户提交的表单：补齐默认值、转换类型并检查必填项
+///
+/// 各渠道提交的值可能是字符串（如 Telegram 文本），这里统一转换为字段声明的类型
+pub fn normalize_form_values(fields: &[FormField], submitted: Option<&Map<String, Value>>) -> FormValues {
+    let mut result = FormValues::default();
+
+    for field in fields {
+        let submitted_value = submitted
+            .and_then(|values| values.get(&field.name))
+            .filter(|value| !is_empty_value(value));
+
+        let value = match submitted_value.or(field.default.as_ref()) {
+            Some(value) => match coerce_value(field, value) {
+                Some(value) => value,
+                None => {
+                    result.invalid.push(field.name.clone());
+                    Value::Null
+                }
+            },
+            None => {
+                if field.required {
+                    result.missing.push(field.name.clone());
+                }
+                Value::Null
+            }
+        };
+
+        result.values.insert(field.name.clone(), value);
+    }
+
+    result
+}
+
+/// 从 `字段: 值` 形式的文本中提取表单值，返回提取结果和剩余文本
+///
+/// 字段可以用字段名或标题指定，分隔符支持 `:`、`：` 和 `=`
+pub fn parse_form_text(fields: &[FormField], text: &str) -> (Map<String, Value>, String) {
+    let mut values = Map::new();
+    let mut remaining = Vec::new();
+
+    for line in text.lines() {
+        let parsed = line
+            .split_once([':', '：', '='])
+            .and_then(|(key, value)| {
+                let key = key.trim();
+                fields
+                    .iter()
+                    .find(|f| f.name.eq_ignore_ascii_case(key) || f.display_label() == key)
+                    .map(|field| (field, value.trim()))
+            });
+
+        match parsed {
+            Some((field, value)) => {
+                values.insert(field.name.clone(), Value::String(value.to_string()));
+            }
+            None => remaining.push(line),
+        }
+    }
+
+    (values, remaining.join("\n").trim().to_string())
+}
+
+/// 生成表单结果的文本摘要
+pub fn format_form_summary(fields: &[FormField], form: &FormValues) -> String {
+    let mut lines = vec!["表单结果:".to_string()];
+    for field in fields {
+        let value = form.values.get(&field.name).unwrap_or(&Value::Null);
+        let display = match value {
+            Value::Null => "（未填写）".to_string(),
+            Value::String(s) => s.clone(),
+            Value::Array(items) => items
+                .iter()
+                .map(|v| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            other => other.to_string(),
+        };
+        lines.push(format!("- {} ({}): {}", field.display_label(), field.name, display));
+    }
+
+    if !form.missing.is_empty() {
+        lines.push(format!("⚠️ 必填字段未填写: {}", form.missing.join(", ")));
+    }
+    if !form.invalid.is_empty() {
+        lines.push(format!("⚠️ 字段值格式无效: {}", form.invalid.join(", ")));
+    }
+
+    lines.join("\n")
+}
+
+fn is_empty_value(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        _ => false,
+    }
+}
+
+fn coerce_value(field: &FormField, value: &Value) -> Option<Value> {
+    match field.field_type {
+        FormFieldType::Text | FormFieldType::FilePath => match value {
+            Value::String(s) => Some(Value::String(s.trim().to_string())),
+            Value::Number(_) | Value::Bool(_) => Some(Value::String(value.to_string())),
+            _ => None,
+        },
+        FormFieldType::Number => match value {
+            Value::Number(_) => Some(value.clone()),
+            Value::String(s) => parse_number(s.trim()),
+            _ => None,
+        },
+        FormFieldType::Boolean => match value {
+            Value::Bool(_) => Some(value.clone()),
+            Value::String(s) => parse_bool(s.trim()).map(Value::Bool),
+            _ => None,
+        },
+        FormFieldType::Select => {
+            let choice = value_as_string(value)?;
+            is_valid_option(field, &choice).then(|| Value::String(choice))
+        }
+        FormFieldType::MultiSelect => {
+            let choices: Vec<String> = match value {
+                Value::Array(items) => items.iter().map(value_as_string).collect::<Option<_>>()?,
+                Value::String(s) => s
+                    .split([',', '，'])
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect(),
+                _ => return None,
+            };
+            choices
+                .iter()
+                .all(|choice| is_valid_option(field, choice))
+                .then(|| Value::Array(choices.into_iter().map(Value::String).collect()))
+        }
+    }
+}
+
+fn value_as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(_) | Value::Bool(_) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+fn is_valid_option(field: &FormField, choice: &str) -> bool {
+    field.options.is_empty() || field.options.iter().any(|option| option == choice)
+}
+
+fn parse_number(text: &str) -> Option<Value> {
+    if let Ok(int) = text.parse::<i64>() {
+        return Some(Value::from(int));
+    }
+    text.parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map(Value::Number)
+}
+
+fn parse_bool(text: &str) -> Option<bool> {
+    match text.to_lowercase().as_str() {
+        "true" | "yes" | "y" | "on" | "1" | "是" | "开启" => Some(true),
+        "false" | "no" | "n" | "off" | "0" | "否" | "关闭" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields() -> Vec<FormField> {
+        serde_json::from_value(json!([
+            {"name": "db", "type": "select", "options": ["postgres", "mysql"], "required": true},
+            {"name": "replicas", "label": "副本数", "type": "number", "default": 1},
+            {"name": "tls", "type": "boolean"},
+            {"name": "regions", "type": "multi_select", "options": ["cn", "us", "eu"]}
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_normalize_coerces_and_fills_defaults() {
+        let submitted = json!({"db": "postgres", "tls": "是", "regions": "cn, eu"});
+        let form = normalize_form_values(&fields(), submitted.as_object());
+
+        assert_eq!(
+            Value::Object(form.values),
+            json!({"db": "postgres", "replicas": 1, "tls": true, "regions": ["cn", "eu"]})
+        );
+        assert!(form.missing.is_empty());
+        assert!(form.invalid.is_empty());
+    }
+
+    #[test]
+    fn test_normalize_reports_missing_and_invalid() {
+        let submitted = json!({"replicas": "three", "regions": ["jp"]});
+        let form = normalize_form_values(&fields(), submitted.as_object());
+
+        assert_eq!(form.missing, vec!["db"]);
+        assert_eq!(form.invalid, vec!["replicas", "regions"]);
+        assert_eq!(form.values["tls"], Value::Null);
+    }
+
+    #[test]
+    fn test_parse_form_text() {
+        let (values, remaining) = parse_form_text(&fields(), "db: mysql\n副本数：3\n顺便看下日志");
+        assert_eq!(Value::Object(values), json!({"db": "mysql", "replicas": "3"}));
+        assert_eq!(remaining, "顺便看下日志");
+    }
+}
@@ -1,5 +1,9 @@
+pub mod batch;
+pub mod form;
 pub mod popup;
 pub mod response;
 
+pub use batch::*;
+pub use form::*;
 pub use popup::*;
 pub use response::*;
@@ -1,31 +1,58 @@
 use anyhow::Result;
 use std::process::Command;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::mcp::types::PopupRequest;
+use crate::daemon::send_to_daemon;
+use crate::mcp::types::{NotifyRequest, PopupRequest};
+use crate::log_important;
 
 /// 创建 Tauri 弹窗
 ///
-/// 优先调用与 MCP 服务器同目录的 UI 命令，找不到时使用全局版本
-pub fn create_tauri_popup(request: &PopupRequest) -> Result<String> {
+/// 优先交给常驻的等一下进程，未运行时调用与 MCP 服务器同目录的 UI 命令，找不到时使用全局版本。
+/// 返回的 future 被丢弃时（如其他渠道已作答）会关闭对应的弹窗
+pub async fn create_tauri_popup(request: &PopupRequest) -> Result<String> {
+    match send_to_daemon(request).await {
+        Ok(Some(response)) => return Ok(response),
+        Ok(None) => {}
+        Err(e) => log_important!(warn, "弹窗常驻进程处理失败，改用独立弹窗: {}", e),
+    }
+
+    create_subprocess_popup(request).await
+}
+
+/// 弹窗请求的临时文件，离开作用域时删除
+///
+/// 其他渠道先作答时弹窗的 future 会被直接丢弃，删除放在 `Drop` 中才能执行到
+struct TempRequestFile(PathBuf);
+
+impl Drop for TempRequestFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// 启动独立的等一下进程显示弹窗
+async fn create_subprocess_popup(request: &PopupRequest) -> Result<String> {
     // 创建临时请求文件 - 跨平台适配
     let temp_dir = std::env::temp_dir();
-    let temp_file = temp_dir.join(format!("mcp_request_{}.json", request.id));
+    let temp_file = TempRequestFile(temp_dir.join(format!("mcp_request_{}.json", request.id)));
     let request_json = serde_json::to_string_pretty(request)?;
-    fs::write(&temp_file, request_json)?;
+    fs::write(&temp_file.0, request_json)?;
 
     // 尝试找到等一下命令的路径
     let command_path = find_ui_command()?;
 
-    // 调用等一下命令
-    let output = Command::new(&command_path)
+    // 调用等一下命令，请求被撤回时随 future 一起结束进程
+    let output = tokio::process::Command::new(&command_path)
         .arg("--mcp-request")
-        .arg(temp_file.to_string_lossy().to_string())
-        .output()?;
+        .arg(temp_file.0.to_string_lossy().to_string())
+        .kill_on_drop(true)
+        .output()
+        .await;
 
-    // 清理临时文件
-    let _ = fs::remove_file(&temp_file);
+    drop(temp_file);
+    let output = output?;
 
     if output.status.success() {
         let response = String::from_utf8_lossy(&output.stdout);
@@ -41,6 +68,41 @@ pub fn create_tauri_popup(request: &PopupRequest) -> Result<String> {
     }
 }
 
+/// 启动独立的等一下进程显示桌面通知
+///
+/// 通知窗口显示几秒后自行关闭并播放提示音，这里只负责启动进程，不等待其结束
+pub fn create_tauri_notification(request: &NotifyRequest) -> Result<()> {
+    let temp_file = std::env::temp_dir().join(format!("mcp_notify_{}.json", uuid::Uuid::new_v4()));
+    fs::write(&temp_file, serde_json::to_string_pretty(request)?)?;
+
+    let command_path = find_ui_command()?;
+    let child = tokio::process::Command::new(&command_path)
+        .arg("--notify")
+        .arg(temp_file.to_string_lossy().to_string())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = fs::remove_file(&temp_file);
+            return Err(e.into());
+        }
+    };
+
+    // 在后台回收进程并清理临时文件
+    tokio::spawn(async move {
+        if let Err(e) = child.wait().await {
+            log_important!(warn, "等待通知进程结束失败: {}", e);
+        }
+        let _ = fs::remove_file(&temp_file);
+    });
+
+    Ok(())
+}
+
 /// 查找等一下 UI 命令的路径
 ///
 /// 按优先级查找：同目录 -> 全局版本 -> 开发环境
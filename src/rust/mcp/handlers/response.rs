@@ -186,8 +186,8 @@ fn parse_structured_response(response: McpResponse) -> Result<Vec<Content>, McpE
         result.push(Content::text(combined_text));
     }
 
-    // 7. 如果没有任何内容，添加默认响应
-    if result.is_empty() {
+    // 7. 如果没有任何内容，添加默认响应（表单结果和批量回答由调用方单独追加）
+    if result.is_empty() && response.form_values.is_none() && response.answers.is_none() {
         result.push(Content::text("用户未提供任何内容".to_string()));
     }
 
@@ -1,4 +1,6 @@
+pub mod channels;
 pub mod commands;
+pub mod prompts;
 pub mod server;
 pub mod tools;
 pub mod types;
@@ -6,6 +8,7 @@ pub mod handlers;
 pub mod utils;
 
 pub use commands::*;
+pub use prompts::*;
 pub use server::*;
 pub use tools::*;
 pub use types::*;
@@ -0,0 +1,193 @@
+use anyhow::Result;
+use rmcp::{ErrorData as McpError, model::*};
+use std::collections::HashSet;
+
+use crate::config::{config_watcher, CustomPrompt};
+
+/// 自定义 prompt 的 MCP 发布
+///
+/// 普通 prompt 通过 `prompts/list` / `prompts/get` 发布；条件性 prompt 作为每个普通 prompt 的
+/// 可选参数，参数值决定拼接 `template_true` 还是 `template_false`，未传时使用当前开关状态
+pub struct PromptProvider;
+
+impl PromptProvider {
+    /// 列出所有可用的自定义 prompt
+    pub fn list_prompts() -> Result<Vec<Prompt>> {
+        let (normal, conditional) = load_prompts()?;
+        let arguments = build_arguments(&conditional);
+
+        Ok(normal
+            .iter()
+            .map(|(name, prompt)| {
+                let mut mcp_prompt = Prompt::new(
+                    name.clone(),
+                    prompt.description.clone(),
+                    if arguments.is_empty() { None } else { Some(arguments.clone()) },
+                );
+                mcp_prompt.title = Some(prompt.name.clone());
+                mcp_prompt
+            })
+            .collect())
+    }
+
+    /// 获取指定 prompt 的内容
+    pub fn get_prompt(request: GetPromptRequestParam) -> Result<GetPromptResult, McpError> {
+        let (normal, conditional) = load_prompts()
+            .map_err(|e| McpError::internal_error(format!("加载自定义prompt失败: {}", e), None))?;
+
+        let (_, prompt) = normal
+            .iter()
+            .find(|(name, _)| *name == request.name)
+            .ok_or_else(|| McpError::invalid_params(format!("未知的prompt: {}", request.name), None))?;
+
+        let arguments = request.arguments.unwrap_or_default();
+        let mut states = Vec::new();
+        for (name, conditional_prompt) in &conditional {
+            let state = match arguments.get(name) {
+                Some(value) => parse_switch(value).ok_or_else(|| McpError::invalid_params(
+                    format!("参数 {} 的值无效，请使用 true 或 false", name),
+                    None,
+                ))?,
+                None => conditional_prompt.current_state,
+            };
+            states.push((conditional_prompt, state));
+        }
+
+        Ok(GetPromptResult {
+            description: prompt.description.clone(),
+            messages: vec![PromptMessage::new_text(
+                PromptMessageRole::User,
+                render_prompt(prompt, &states),
+            )],
+        })
+    }
+}
+
+/// 读取配置中的 prompt，拆分为普通 prompt 和条件性 prompt，并分配 MCP 名称
+fn load_prompts() -> Result<(Vec<(String, CustomPrompt)>, Vec<(String, CustomPrompt)>)> {
+    let config = config_watcher().current().custom_prompt_config;
+    if !config.enabled {
+        return Ok((vec![], vec![]));
+    }
+
+    let mut prompts = config.prompts;
+    prompts.sort_by_key(|p| p.sort_order);
+
+    let mut used_names = HashSet::new();
+    let mut normal = Vec::new();
+    let mut conditional = Vec::new();
+
+    for prompt in prompts {
+        if prompt.r#type == "conditional" {
+            let name = unique_name(&prompt, &mut used_names);
+            conditional.push((name, prompt));
+        } else if !prompt.content.trim().is_empty() {
+            // 内容为空的普通 prompt（如清空输入框）只在界面中有意义
+            let name = unique_name(&prompt, &mut used_names);
+            normal.push((name, prompt));
+        }
+    }
+
+    Ok((normal, conditional))
+}
+
+fn build_arguments(conditional: &[(String, CustomPrompt)]) -> Vec<PromptArgument> {
+    conditional
+        .iter()
+        .map(|(name, prompt)| PromptArgument {
+            name: name.clone(),
+            title: Some(prompt.name.clone()),
+            description: Some(format!(
+                "{}（true/false，默认 {}）",
+                prompt.condition_text.clone().unwrap_or_else(|| prompt.name.clone()),
+                prompt.current_state
+            )),
+            required: Some(false),
+        })
+        .collect()
+}
+
+/// 由 prompt 名称生成 MCP 名称，名称无可用字符或重复时回退到 id
+fn unique_name(prompt: &CustomPrompt, used_names: &mut HashSet<String>) -> String {
+    let slug = slugify(&prompt.name);
+    let name = if slug.is_empty() || used_names.contains(&slug) {
+        slugify(&prompt.id)
+    } else {
+        slug
+    };
+    used_names.insert(name.clone());
+    name
+}
+
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for ch in text.chars() {
+        if ch.is_alphanumeric() {
+            slug.extend(ch.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+fn parse_switch(value: &serde_json::Value) -> Option<bool> {
+    match value {
+        serde_json::Value::Bool(b) => Some(*b),
+        serde_json::Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" | "是" => Some(true),
+            "false" | "no" | "off" | "0" | "否" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// 拼接 prompt 内容和条件性模板，与弹窗中的拼接方式保持一致
+fn render_prompt(prompt: &CustomPrompt, states: &[(&CustomPrompt, bool)]) -> String {
+    let conditional_texts: Vec<String> = states
+        .iter()
+        .filter_map(|(conditional, state)| {
+            let template = if *state {
+                conditional.template_true.as_ref()
+            } else {
+                conditional.template_false.as_ref()
+            };
+            template.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+        })
+        .collect();
+
+    if conditional_texts.is_empty() {
+        prompt.content.clone()
+    } else {
+        format!("{}\n\n{}", prompt.content, conditional_texts.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_custom_prompts;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("🔍Review And Plan"), "review-and-plan");
+        assert_eq!(slugify("是否主动编译"), "是否主动编译");
+        assert_eq!(slugify("✅"), "");
+    }
+
+    #[test]
+    fn test_render_prompt_with_conditionals() {
+        let prompts = default_custom_prompts();
+        let normal = prompts.iter().find(|p| p.id == "default_3").unwrap();
+        let compile = prompts.iter().find(|p| p.id == "default_9").unwrap();
+        let run = prompts.iter().find(|p| p.id == "default_10").unwrap();
+
+        let rendered = render_prompt(normal, &[(compile, true), (run, false)]);
+        assert_eq!(
+            rendered,
+            "ok，完美，新的需求or问题，\n\n✔️请记住，帮我编译\n❌请记住，不要运行，用户自己运行"
+        );
+        assert_eq!(render_prompt(normal, &[]), normal.content);
+    }
+}
@@ -3,19 +3,32 @@ use rmcp::{
     ErrorData as McpError, ServerHandler, ServiceExt, RoleServer,
     model::*,
     transport::stdio,
-    service::RequestContext,
+    transport::streamable_http_server::{
+        StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+    },
+    service::{NotificationContext, Peer, RequestContext},
 };
-use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
 
-use super::tools::{InteractionTool, MemoryTool, EnhanceTool, AcemcpTool};
-use super::types::{ZhiRequest, JiyiRequest};
+use super::tools::{InteractionContext, InteractionTool, MemoryTool, EnhanceTool, AcemcpTool, ReviewTool, NotifyTool};
+use super::types::{ClientIdentity, ZhiRequest, JiyiRequest, ShenRequest, NotifyRequest};
 use super::tools::enhance::EnhanceRequest;
-use crate::config::load_standalone_config;
+use super::tools::memory::MemoryResources;
+use super::prompts::PromptProvider;
+use super::utils::{generate_request_id, ProgressReporter};
+use crate::config::config_watcher;
+use crate::constants::mcp;
 use crate::{log_important, log_debug};
 
 #[derive(Clone)]
 pub struct ZhiServer {
-    enabled_tools: HashMap<String, bool>,
+    /// 会话标识，HTTP 模式下每个客户端会话各有一个实例
+    session_id: String,
+    /// `initialize` 握手时客户端上报的名称和版本
+    client_info: Arc<OnceLock<Implementation>>,
 }
 
 impl Default for ZhiServer {
@@ -26,31 +39,61 @@ impl Default for ZhiServer {
 
 impl ZhiServer {
     pub fn new() -> Self {
-        // 尝试加载配置，如果失败则使用默认配置
-        let enabled_tools = match load_standalone_config() {
-            Ok(config) => config.mcp_config.tools,
-            Err(e) => {
-                log_important!(warn, "无法加载配置文件，使用默认工具配置: {}", e);
-                crate::config::default_mcp_tools()
-            }
-        };
+        // 初始化配置监听器，后续工具状态均从内存中的配置读取
+        let _ = config_watcher();
+        Self {
+            session_id: generate_request_id(),
+            client_info: Arc::new(OnceLock::new()),
+        }
+    }
 
-        Self { enabled_tools }
+    /// 当前连接的客户端身份，握手前只有会话标识
+    fn client_identity(&self) -> ClientIdentity {
+        let info = self.client_info.get();
+        ClientIdentity {
+            session_id: self.session_id.clone(),
+            name: info.map(|info| info.name.clone()),
+            version: info.map(|info| info.version.clone()),
+        }
     }
 
-    /// 检查工具是否启用 - 动态读取最新配置
+    /// 检查工具是否启用 - 读取监听器维护的最新配置
     fn is_tool_enabled(&self, tool_name: &str) -> bool {
-        // 每次都重新读取配置，确保获取最新状态
-        match load_standalone_config() {
-            Ok(config) => {
-                let enabled = config.mcp_config.tools.get(tool_name).copied().unwrap_or(true);
-                log_debug!("工具 {} 当前状态: {}", tool_name, enabled);
-                enabled
+        let enabled = config_watcher()
+            .current()
+            .mcp_config
+            .tools
+            .get(tool_name)
+            .copied()
+            .unwrap_or(true);
+        log_debug!("工具 {} 当前状态: {}", tool_name, enabled);
+        enabled
+    }
+
+    /// 将配置变更转发给客户端，直到连接关闭
+    async fn forward_config_changes(peer: Peer<RoleServer>) {
+        let mut changes = config_watcher().subscribe();
+
+        loop {
+            let change = match changes.recv().await {
+                Ok(change) => change,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            if change.tools_changed {
+                if let Err(e) = peer.notify_tool_list_changed().await {
+                    log_debug!("发送工具列表变更通知失败，停止转发: {}", e);
+                    break;
+                }
+                log_important!(info, "已通知客户端工具列表变更");
             }
-            Err(e) => {
-                log_important!(warn, "读取配置失败，使用缓存状态: {}", e);
-                // 如果读取失败，使用缓存的配置
-                self.enabled_tools.get(tool_name).copied().unwrap_or(true)
+
+            if change.prompts_changed {
+                if let Err(e) = peer.notify_prompt_list_changed().await {
+                    log_debug!("发送prompt列表变更通知失败，停止转发: {}", e);
+                    break;
+                }
             }
         }
     }
@@ -59,8 +102,15 @@ impl ZhiServer {
 impl ServerHandler for ZhiServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
-            protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            // elicitation 需要 2025-06-18 及以后的协议版本
+            protocol_version: ProtocolVersion::V_2025_06_18,
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .enable_resources()
+                .enable_prompts()
+                .enable_prompts_list_changed()
+                .build(),
             server_info: Implementation {
                 name: "Zhi-mcp".to_string(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
@@ -74,10 +124,28 @@ impl ServerHandler for ZhiServer {
 
     async fn initialize(
         &self,
-        _request: InitializeRequestParam,
+        request: InitializeRequestParam,
         _context: RequestContext<RoleServer>,
     ) -> Result<ServerInfo, McpError> {
-        Ok(self.get_info())
+        log_important!(
+            info,
+            "客户端已连接: {} {} (会话 {})",
+            request.client_info.name,
+            request.client_info.version,
+            self.session_id
+        );
+        let _ = self.client_info.set(request.client_info);
+
+        // 客户端只支持较旧的协议时沿用其版本
+        let mut info = self.get_info();
+        if [ProtocolVersion::V_2024_11_05, ProtocolVersion::V_2025_03_26].contains(&request.protocol_version) {
+            info.protocol_version = request.protocol_version;
+        }
+        Ok(info)
+    }
+
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        tokio::spawn(Self::forward_config_changes(context.peer));
     }
 
     async fn list_tools(
@@ -85,30 +153,105 @@ impl ServerHandler for ZhiServer {
         _request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> Result<ListToolsResult, McpError> {
-        use std::sync::Arc;
         use std::borrow::Cow;
 
         let mut tools = Vec::new();
 
         // 寸止工具始终可用（必需工具）
+        let option_schema = serde_json::json!({
+            "anyOf": [
+                {"type": "string"},
+                {
+                    "type": "object",
+                    "properties": {
+                        "label": {"type": "string", "description": "显示给用户的选项文本"},
+                        "description": {"type": "string", "description": "选项说明（可选）"},
+                        "value": {"type": "string", "description": "选中后返回的值（可选，默认使用 label）"},
+                        "recommended": {"type": "boolean", "description": "是否为推荐选项，默认为false"}
+                    },
+                    "required": ["label"]
+                }
+            ]
+        });
+        let selection_mode_schema = serde_json::json!({
+            "type": "string",
+            "enum": ["single", "multi", "none"],
+            "description": "选项的选择方式：single（单选）、multi（多选，默认）、none（仅展示不可选）"
+        });
         let zhi_schema = serde_json::json!({
             "type": "object",
             "properties": {
                 "message": {
                     "type": "string",
-                    "description": "要显示给用户的消息"
+                    "description": "要显示给用户的消息；批量提问时作为总体说明（可省略）"
                 },
                 "predefined_options": {
                     "type": "array",
-                    "items": {"type": "string"},
-                    "description": "预定义的选项列表（可选）"
+                    "items": option_schema,
+                    "description": "预定义的选项列表（可选），每项为字符串或 {label, description, value, recommended} 对象"
                 },
+                "selection_mode": selection_mode_schema,
                 "is_markdown": {
                     "type": "boolean",
                     "description": "消息是否为Markdown格式，默认为true"
+                },
+                "timeout_seconds": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "等待用户回答的超时时间（秒，可选），超时后自动回复默认选项或继续提示词；批量提问时所有问题共用该时限"
+                },
+                "default_option": {
+                    "type": "string",
+                    "description": "超时后自动选择的选项（可选），未设置时回复继续提示词"
+                },
+                "fields": {
+                    "type": "array",
+                    "description": "结构化表单字段（可选），用户填写的结果以 structured_content 返回",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": {"type": "string", "description": "字段名，作为返回结果中的键"},
+                            "label": {"type": "string", "description": "显示给用户的标题（可选，默认使用字段名）"},
+                            "type": {
+                                "type": "string",
+                                "enum": ["text", "number", "boolean", "select", "multi_select", "file_path"]
+                            },
+                            "description": {"type": "string", "description": "字段说明（可选）"},
+                            "options": {
+                                "type": "array",
+                                "items": {"type": "string"},
+                                "description": "select / multi_select 的可选项"
+                            },
+                            "default": {"description": "默认值（可选）"},
+                            "required": {"type": "boolean", "description": "是否必填，默认为false"}
+                        },
+                        "required": ["name", "type"]
+                    }
+                },
+                "project_path": {
+                    "type": "string",
+                    "description": "当前项目路径（可选），用于记录交互历史，之后可通过 ji 的历史操作检索"
+                },
+                "questions": {
+                    "type": "array",
+                    "description": "批量提问（可选）：多个相互独立的问题在一次交互中逐个展示，回答以问题ID为键在 structured_content.answers 中返回",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": "string", "description": "问题ID，回答以此为键返回"},
+                            "message": {"type": "string", "description": "问题内容"},
+                            "predefined_options": {
+                                "type": "array",
+                                "items": option_schema,
+                                "description": "该问题的预定义选项（可选）"
+                            },
+                            "selection_mode": selection_mode_schema
+                        },
+                        "required": ["id", "message"]
+                    }
                 }
             },
-            "required": ["message"]
+            "description": "message 与 questions 至少提供一个"
         });
 
         if let serde_json::Value::Object(schema_map) = zhi_schema {
@@ -192,19 +335,49 @@ impl ServerHandler for ZhiServer {
                 "properties": {
                     "action": {
                         "type": "string",
-                        "description": "操作类型：记忆(添加记忆), 回忆(获取项目信息)"
+                        "description": "操作类型：记忆(添加记忆), 回忆(按相关度获取项目记忆), 更新(按ID修改记忆内容), 删除(按ID删除记忆), 整理(去重、归档过时记忆并调整分类), 历史(检索之前的寸止交互)"
                     },
                     "project_path": {
                         "type": "string",
                         "description": "项目路径（必需）"
                     },
+                    "id": {
+                        "type": "string",
+                        "description": "记忆ID或其前缀（更新、删除操作时必需），可通过回忆操作查看"
+                    },
                     "content": {
                         "type": "string",
-                        "description": "记忆内容（记忆操作时必需）"
+                        "description": "记忆内容（记忆、更新操作时必需）"
                     },
                     "category": {
                         "type": "string",
                         "description": "记忆分类：rule(规范规则), preference(用户偏好), pattern(最佳实践), context(项目上下文)"
+                    },
+                    "scope": {
+                        "type": "string",
+                        "enum": ["project", "global"],
+                        "description": "记忆范围：project(仅当前项目，默认), global(所有项目通用，如个人偏好)；回忆时项目范围会合并全局记忆，冲突时以项目记忆为准；更新、删除只作用于指定范围"
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "回忆操作的检索关键词（可选），按相关度返回记忆，规范类记忆始终排在最前；历史操作时为空格分隔的关键词"
+                    },
+                    "since": {
+                        "type": "string",
+                        "description": "历史操作只返回该时间之后的交互（可选）：RFC 3339 时间、YYYY-MM-DD 日期或 7d 形式的最近天数"
+                    },
+                    "categories": {
+                        "type": "array",
+                        "items": {
+                            "type": "string",
+                            "enum": ["rule", "preference", "pattern", "context"]
+                        },
+                        "description": "回忆操作限定的记忆分类（可选）"
+                    },
+                    "max_chars": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "回忆操作输出的最大字符数（可选），默认 4000"
                     }
                 },
                 "required": ["action", "project_path"]
@@ -229,6 +402,85 @@ impl ServerHandler for ZhiServer {
             tools.push(AcemcpTool::get_tool_definition());
         }
 
+        // 代码审查工具 - 仅在启用时添加
+        if self.is_tool_enabled("shen") {
+            let shen_schema = serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "project_path": {
+                        "type": "string",
+                        "description": "项目路径（必需）"
+                    },
+                    "diff": {
+                        "type": "string",
+                        "description": "要审查的 unified diff（与 revision_range 二选一）"
+                    },
+                    "revision_range": {
+                        "type": "string",
+                        "description": "要审查的 git 修订范围，如 HEAD~1..HEAD（与 diff 二选一，都未提供时审查未提交的改动）"
+                    },
+                    "message": {
+                        "type": "string",
+                        "description": "显示给用户的说明（可选）"
+                    }
+                },
+                "required": ["project_path"]
+            });
+
+            if let serde_json::Value::Object(schema_map) = shen_schema {
+                tools.push(Tool {
+                    name: Cow::Borrowed("shen"),
+                    description: Some(Cow::Borrowed("代码审查工具，在弹窗中按文件、按代码块展示改动，由用户逐块接受或拒绝并填写评论，返回结构化的审查结论")),
+                    input_schema: Arc::new(schema_map),
+                    annotations: None,
+                    icons: None,
+                    meta: None,
+                    output_schema: None,
+                    title: None,
+                });
+            }
+        }
+
+        // 进度通知工具 - 仅在启用时添加
+        if self.is_tool_enabled("notify") {
+            let notify_schema = serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "message": {
+                        "type": "string",
+                        "description": "通知内容"
+                    },
+                    "title": {
+                        "type": "string",
+                        "description": "通知标题（可选）"
+                    },
+                    "level": {
+                        "type": "string",
+                        "enum": ["info", "success", "warning", "error"],
+                        "description": "通知级别，默认为 info"
+                    },
+                    "is_markdown": {
+                        "type": "boolean",
+                        "description": "通知内容是否为Markdown格式，默认为true"
+                    }
+                },
+                "required": ["message"]
+            });
+
+            if let serde_json::Value::Object(schema_map) = notify_schema {
+                tools.push(Tool {
+                    name: Cow::Borrowed("notify"),
+                    description: Some(Cow::Borrowed("进度通知工具，向桌面、Telegram 发送通知并播放提示音，立即返回各渠道的发送状态（Telegram 在后台发送，状态为已排队），不等待用户回复。适合汇报进度，需要用户决策时请使用 zhi")),
+                    input_schema: Arc::new(schema_map),
+                    annotations: None,
+                    icons: None,
+                    meta: None,
+                    output_schema: None,
+                    title: None,
+                });
+            }
+        }
+
         log_debug!("返回给客户端的工具列表: {:?}", tools.iter().map(|t| &t.name).collect::<Vec<_>>());
 
         Ok(ListToolsResult {
@@ -237,10 +489,80 @@ impl ServerHandler for ZhiServer {
         })
     }
 
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        // 记忆资源依附于记忆管理工具，工具禁用时不对外发布
+        if !self.is_tool_enabled("ji") {
+            return Ok(ListResourcesResult {
+                resources: vec![],
+                next_cursor: None,
+            });
+        }
+
+        let resources = MemoryResources::list_resources()
+            .map_err(|e| McpError::internal_error(format!("获取记忆资源列表失败: {}", e), None))?;
+
+        log_debug!("返回给客户端的资源数量: {}", resources.len());
+
+        Ok(ListResourcesResult {
+            resources,
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        log_debug!("收到资源读取请求: {}", request.uri);
+
+        if !self.is_tool_enabled("ji") {
+            return Err(McpError::internal_error(
+                "记忆管理工具已被禁用".to_string(),
+                None
+            ));
+        }
+
+        let contents = MemoryResources::read_resource(&request.uri)?;
+
+        Ok(ReadResourceResult {
+            contents: vec![contents],
+        })
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        let prompts = PromptProvider::list_prompts()
+            .map_err(|e| McpError::internal_error(format!("获取自定义prompt列表失败: {}", e), None))?;
+
+        log_debug!("返回给客户端的prompt列表: {:?}", prompts.iter().map(|p| &p.name).collect::<Vec<_>>());
+
+        Ok(ListPromptsResult {
+            prompts,
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        log_debug!("收到prompt获取请求: {}", request.name);
+        PromptProvider::get_prompt(request)
+    }
+
     async fn call_tool(
         &self,
         request: CallToolRequestParam,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         log_debug!("收到工具调用请求: {}", request.name);
 
@@ -255,7 +577,9 @@ impl ServerHandler for ZhiServer {
                     .map_err(|e| McpError::invalid_params(format!("参数解析失败: {}", e), None))?;
 
                 // 调用寸止工具
-                InteractionTool::zhi(zhi_request).await
+                let interaction_context = InteractionContext::with_peer(context.peer.clone())
+                    .with_client(self.client_identity());
+                InteractionTool::zhi_with_context(zhi_request, &interaction_context).await
             }
             "enhance" => {
                 // 解析请求参数
@@ -307,8 +631,49 @@ impl ServerHandler for ZhiServer {
                 let acemcp_request: crate::mcp::tools::acemcp::types::AcemcpRequest = serde_json::from_value(arguments_value)
                     .map_err(|e| McpError::invalid_params(format!("参数解析失败: {}", e), None))?;
 
-                // 调用代码搜索工具
-                AcemcpTool::search_context(acemcp_request).await
+                // 调用代码搜索工具，上报索引进度并响应客户端取消
+                let progress = ProgressReporter::from_context(&context);
+                AcemcpTool::search_context_with_progress(acemcp_request, progress, context.ct.clone()).await
+            }
+            "shen" => {
+                // 检查代码审查工具是否启用
+                if !self.is_tool_enabled("shen") {
+                    return Err(McpError::internal_error(
+                        "代码审查工具已被禁用".to_string(),
+                        None
+                    ));
+                }
+
+                // 解析请求参数
+                let arguments_value = request.arguments
+                    .map(serde_json::Value::Object)
+                    .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
+
+                let shen_request: ShenRequest = serde_json::from_value(arguments_value)
+                    .map_err(|e| McpError::invalid_params(format!("参数解析失败: {}", e), None))?;
+
+                // 调用代码审查工具
+                ReviewTool::shen(shen_request).await
+            }
+            "notify" => {
+                // 检查进度通知工具是否启用
+                if !self.is_tool_enabled("notify") {
+                    return Err(McpError::internal_error(
+                        "进度通知工具已被禁用".to_string(),
+                        None
+                    ));
+                }
+
+                // 解析请求参数
+                let arguments_value = request.arguments
+                    .map(serde_json::Value::Object)
+                    .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
+
+                let notify_request: NotifyRequest = serde_json::from_value(arguments_value)
+                    .map_err(|e| McpError::invalid_params(format!("参数解析失败: {}", e), None))?;
+
+                // 发送通知，不等待用户回复
+                NotifyTool::notify(notify_request).await
             }
             _ => {
                 Err(McpError::invalid_request(
@@ -320,10 +685,93 @@ impl ServerHandler for ZhiServer {
     }
 }
 
+/// MCP 服务器传输方式
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerTransport {
+    /// 标准输入输出（由客户端拉起子进程）
+    Stdio,
+    /// Streamable HTTP，服务端到客户端的消息通过 SSE 推送
+    Http { bind: SocketAddr },
+}
 
+/// 允许监听非回环地址的命令行开关
+///
+/// HTTP 服务没有鉴权，监听外部地址会把所有工具和项目记忆暴露给网络
+const ALLOW_REMOTE_FLAG: &str = "--allow-remote";
+
+impl ServerTransport {
+    /// 从命令行参数解析传输方式
+    ///
+    /// 支持 `--transport stdio|http` 与 `--bind 127.0.0.1:PORT`，未指定时使用 stdio；
+    /// 非回环地址需要同时指定 `--allow-remote`
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let mut transport = "stdio".to_string();
+        let mut bind: Option<String> = None;
+        let mut allow_remote = false;
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--transport" => {
+                    transport = iter.next()
+                        .ok_or_else(|| anyhow::anyhow!("--transport 缺少参数值"))?
+                        .clone();
+                }
+                "--bind" => {
+                    bind = Some(iter.next()
+                        .ok_or_else(|| anyhow::anyhow!("--bind 缺少参数值"))?
+                        .clone());
+                }
+                ALLOW_REMOTE_FLAG => allow_remote = true,
+                other => anyhow::bail!("未知参数: {}", other),
+            }
+        }
 
-/// 启动MCP服务器
+        match transport.as_str() {
+            "stdio" => {
+                if bind.is_some() || allow_remote {
+                    anyhow::bail!("--bind 和 {} 仅在 --transport http 下有效", ALLOW_REMOTE_FLAG);
+                }
+                Ok(ServerTransport::Stdio)
+            }
+            "http" => {
+                let bind = bind.unwrap_or_else(|| mcp::DEFAULT_HTTP_BIND.to_string());
+                let bind: SocketAddr = bind.parse()
+                    .map_err(|e| anyhow::anyhow!("无效的监听地址 {}: {}", bind, e))?;
+                if !bind.ip().is_loopback() {
+                    if !allow_remote {
+                        anyhow::bail!(
+                            "拒绝监听非回环地址 {}：HTTP 服务没有鉴权，会把交互工具和项目记忆暴露给网络；确需如此请加上 {}",
+                            bind, ALLOW_REMOTE_FLAG
+                        );
+                    }
+                    log_important!(
+                        warn,
+                        "⚠️ MCP HTTP 服务将监听非回环地址 {}，服务没有鉴权，网络中任何人都可以调用工具并读取已登记项目的记忆",
+                        bind
+                    );
+                }
+                Ok(ServerTransport::Http { bind })
+            }
+            other => anyhow::bail!("不支持的传输方式: {}（可选 stdio, http）", other),
+        }
+    }
+}
+
+/// 启动MCP服务器（stdio 传输）
 pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
+    run_server_with_transport(ServerTransport::Stdio).await
+}
+
+/// 按指定传输方式启动MCP服务器
+pub async fn run_server_with_transport(transport: ServerTransport) -> Result<(), Box<dyn std::error::Error>> {
+    match transport {
+        ServerTransport::Stdio => serve_stdio().await,
+        ServerTransport::Http { bind } => serve_http(bind).await,
+    }
+}
+
+async fn serve_stdio() -> Result<(), Box<dyn std::error::Error>> {
     // 创建并运行服务器
     let service = ZhiServer::new()
         .serve(stdio())
@@ -335,4 +783,38 @@ pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
     // 等待服务器关闭
     service.waiting().await?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// 通过 Streamable HTTP 提供服务
+///
+/// 每个客户端会话对应一个独立的 ZhiServer 实例，多个容器内的代理可共享同一个常驻进程
+async fn serve_http(bind: SocketAddr) -> Result<(), Box<dyn std::error::Error>> {
+    let config = StreamableHttpServerConfig {
+        sse_keep_alive: Some(Duration::from_secs(mcp::HTTP_SSE_KEEP_ALIVE_SECS)),
+        ..Default::default()
+    };
+
+    let service = StreamableHttpService::new(
+        || Ok(ZhiServer::new()),
+        LocalSessionManager::default().into(),
+        config,
+    );
+
+    let router = axum::Router::new().nest_service(mcp::HTTP_ENDPOINT_PATH, service);
+    let listener = tokio::net::TcpListener::bind(bind)
+        .await
+        .inspect_err(|e| {
+            log_important!(error, "监听地址 {} 失败: {}", bind, e);
+        })?;
+
+    log_important!(info, "MCP HTTP 服务已启动: http://{}{}", bind, mcp::HTTP_ENDPOINT_PATH);
+
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+            log_important!(info, "收到退出信号，正在关闭 MCP HTTP 服务");
+        })
+        .await?;
+
+    Ok(())
+}
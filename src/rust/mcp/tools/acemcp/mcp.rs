@@ -16,7 +16,10 @@ use serde::{Deserialize, Serialize};
 use encoding_rs::{GBK, WINDOWS_1252, UTF_8};
 use globset::{Glob, GlobSet, GlobSetBuilder};
 
+use tokio_util::sync::CancellationToken;
+
 use super::types::{AcemcpRequest, AcemcpConfig};
+use crate::mcp::utils::ProgressReporter;
 use crate::log_debug;
 use crate::log_important;
 
@@ -26,6 +29,17 @@ pub struct AcemcpTool;
 impl AcemcpTool {
     /// 执行代码库搜索
     pub async fn search_context(request: AcemcpRequest) -> Result<CallToolResult, McpError> {
+        Self::search_context_with_progress(request, ProgressReporter::none(), CancellationToken::new()).await
+    }
+
+    /// 执行代码库搜索，上报索引进度并响应取消
+    ///
+    /// 取消后停止上传，已上传的 blob 仍会保存到 projects.json
+    pub async fn search_context_with_progress(
+        request: AcemcpRequest,
+        progress: ProgressReporter,
+        ct: CancellationToken,
+    ) -> Result<CallToolResult, McpError> {
         log_important!(info,
             "Acemcp搜索请求: project_root_path={}, query={}",
             request.project_root_path, request.query
@@ -43,7 +57,7 @@ impl AcemcpTool {
         }
 
         // 执行：增量索引（含批量上传）+ 检索
-        match index_and_search(&acemcp_config, &request.project_root_path, &request.query).await {
+        match index_and_search(&acemcp_config, &request.project_root_path, &request.query, &progress, &ct).await {
             Ok(text) => Ok(CallToolResult { 
                 content: vec![Content::text(text)], 
                 is_error: None,
@@ -399,7 +413,13 @@ fn collect_blobs(root: &str, text_exts: &[String], exclude_patterns: &[String],
     Ok(out)
 }
 
-async fn index_and_search(config: &AcemcpConfig, project_root_path: &str, query: &str) -> anyhow::Result<String> {
+async fn index_and_search(
+    config: &AcemcpConfig,
+    project_root_path: &str,
+    query: &str,
+    progress: &ProgressReporter,
+    ct: &CancellationToken,
+) -> anyhow::Result<String> {
     let base_url = config.base_url.clone().ok_or_else(|| anyhow::anyhow!("未配置 base_url"))?;
     // 严格校验 base_url
     let has_scheme = base_url.starts_with("http://") || base_url.starts_with("https://");
@@ -432,6 +452,18 @@ async fn index_and_search(config: &AcemcpConfig, project_root_path: &str, query:
     let blobs = collect_blobs(project_root_path, &text_exts, &exclude_patterns, max_lines)?;
     if blobs.is_empty() { anyhow::bail!("未在项目中找到可索引的文本文件"); }
 
+    let file_count = blobs
+        .iter()
+        .map(|b| b.path.split("#chunk").next().unwrap_or(&b.path))
+        .collect::<std::collections::HashSet<_>>()
+        .len();
+    progress.report(1.0, None, format!("已收集 {} 个文件（{} 个blob）", file_count, blobs.len())).await;
+
+    if ct.is_cancelled() {
+        log_important!(info, "搜索在文件收集后被取消");
+        anyhow::bail!("搜索已取消");
+    }
+
     // 加载 projects.json
     let projects_path = home_projects_file();
     let mut projects: ProjectsFile = if projects_path.exists() {
@@ -470,12 +502,16 @@ async fn index_and_search(config: &AcemcpConfig, project_root_path: &str, query:
 
     let client = Client::new();
 
+    // 进度步骤：文件收集 1 步 + 每个上传批次 1 步 + 检索开始、完成各 1 步
+    let total_batches = (new_blobs.len() + batch_size - 1) / batch_size;
+    let total_steps = (total_batches + 3) as f64;
+
     // 批量上传新增 blobs
     let mut uploaded_names: Vec<String> = Vec::new();
     let mut failed_batches: Vec<usize> = Vec::new();
+    let mut cancelled = false;
     
     if !new_blobs.is_empty() {
-        let total_batches = (new_blobs.len() + batch_size - 1) / batch_size;
         log_important!(info,
             "=== 开始批量上传代码索引 ==="
         );
@@ -488,6 +524,12 @@ async fn index_and_search(config: &AcemcpConfig, project_root_path: &str, query:
         );
         
         for i in 0..total_batches {
+            if ct.is_cancelled() {
+                log_important!(info, "搜索已取消，停止上传，已完成批次: {}/{}", i, total_batches);
+                cancelled = true;
+                break;
+            }
+
             let start = i * batch_size;
             let end = usize::min(start + batch_size, new_blobs.len());
             let batch = &new_blobs[start..end];
@@ -516,7 +558,7 @@ async fn index_and_search(config: &AcemcpConfig, project_root_path: &str, query:
             let payload = serde_json::json!({"blobs": batch});
             log_important!(info, "批次载荷大小: {} 字节", payload.to_string().len());
             
-            match retry_request(|| async {
+            let upload = retry_request(|| async {
                 let r = client
                     .post(&url)
                     .header(AUTHORIZATION, format!("Bearer {}", token))
@@ -536,7 +578,19 @@ async fn index_and_search(config: &AcemcpConfig, project_root_path: &str, query:
                 let v: serde_json::Value = r.json().await?;
                 log_important!(info, "响应数据: {}", serde_json::to_string_pretty(&v).unwrap_or_default());
                 Ok(v)
-            }, 3, 1.0).await {
+            }, 3, 1.0);
+
+            // 上传过程中收到取消时放弃当前批次
+            let result = tokio::select! {
+                _ = ct.cancelled() => {
+                    log_important!(info, "搜索已取消，放弃批次 {}/{}", i + 1, total_batches);
+                    cancelled = true;
+                    break;
+                }
+                result = upload => result,
+            };
+
+            match result {
                 Ok(value) => {
                     if let Some(arr) = value.get("blob_names").and_then(|v| v.as_array()) {
                         let mut batch_names: Vec<String> = Vec::new();
@@ -567,6 +621,12 @@ async fn index_and_search(config: &AcemcpConfig, project_root_path: &str, query:
                     failed_batches.push(i + 1);
                 }
             }
+
+            progress.report(
+                (i + 2) as f64,
+                Some(total_steps),
+                format!("已上传批次 {}/{}", i + 1, total_batches),
+            ).await;
         }
         
         // 上传结果总结
@@ -588,6 +648,11 @@ async fn index_and_search(config: &AcemcpConfig, project_root_path: &str, query:
     projects.0.insert(normalized_root.clone(), all_blob_names.clone());
     if let Ok(s) = serde_json::to_string_pretty(&projects) { let _ = fs::write(projects_path, s); }
 
+    if cancelled {
+        log_important!(info, "搜索已取消，已保存 {} 个blob到索引", all_blob_names.len());
+        anyhow::bail!("搜索已取消，已上传的索引已保存");
+    }
+
     // 使用合并后的 blob_names（与 Python 版本保持一致）
     let blob_names = all_blob_names;
     if blob_names.is_empty() { 
@@ -612,8 +677,9 @@ async fn index_and_search(config: &AcemcpConfig, project_root_path: &str, query:
     });
     
     log_important!(info, "检索载荷大小: {} 字节", payload.to_string().len());
+    progress.report(total_steps - 1.0, Some(total_steps), "开始代码检索").await;
     
-    let value: serde_json::Value = retry_request(|| async {
+    let retrieval = retry_request(|| async {
         let r = client
             .post(&search_url)
             .header(AUTHORIZATION, format!("Bearer {}", token))
@@ -633,7 +699,16 @@ async fn index_and_search(config: &AcemcpConfig, project_root_path: &str, query:
         let v: serde_json::Value = r.json().await?;
         log_important!(info, "检索响应数据: {}", serde_json::to_string_pretty(&v).unwrap_or_default());
         Ok(v)
-    }, 3, 2.0).await?;
+    }, 3, 2.0);
+
+    let value: serde_json::Value = tokio::select! {
+        _ = ct.cancelled() => {
+            log_important!(info, "搜索在检索阶段被取消");
+            anyhow::bail!("搜索已取消");
+        }
+        result = retrieval => result?,
+    };
+    progress.report(total_steps, Some(total_steps), "检索完成").await;
     
     let text = value
         .get("formatted_retrieval")
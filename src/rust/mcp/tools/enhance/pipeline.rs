@@ -43,10 +43,11 @@ pub async fn analyze_requirements(
             full_context
         ),
         predefined_options: vec![
-            "分析完成".to_string(),
-            "需要更多信息".to_string(),
+            "分析完成".into(),
+            "需要更多信息".into(),
         ],
         is_markdown: true,
+        ..Default::default()
     };
     
     // 这里简化处理,实际应该解析用户的详细分析
@@ -92,10 +93,11 @@ pub async fn generate_task_spec(
             analysis.completion
         ),
         predefined_options: vec![
-            "确认任务单".to_string(),
-            "需要修改".to_string(),
+            "确认任务单".into(),
+            "需要修改".into(),
         ],
         is_markdown: true,
+        ..Default::default()
     };
     
     let _ = InteractionTool::zhi(task_request).await?;
@@ -146,10 +148,11 @@ pub async fn generate_code_with_tests(
             task_spec.tech_stack
         ),
         predefined_options: vec![
-            "代码生成完成".to_string(),
-            "需要修改".to_string(),
+            "代码生成完成".into(),
+            "需要修改".into(),
         ],
         is_markdown: true,
+        ..Default::default()
     };
     
     let _ = InteractionTool::zhi(code_request).await?;
@@ -202,10 +205,11 @@ pub async fn scoring_loop(
                 code_result.code
             ),
             predefined_options: vec![
-                "重构完成".to_string(),
-                "需要更多时间".to_string(),
+                "重构完成".into(),
+                "需要更多时间".into(),
             ],
             is_markdown: true,
+            ..Default::default()
         };
         
         let _ = InteractionTool::zhi(review_request).await?;
@@ -0,0 +1,77 @@
+use chrono::Local;
+
+use super::store::{HistoryQuery, HistoryStore};
+use super::types::HistoryRecord;
+use crate::config::get_standalone_config_dir;
+
+/// 查询交互历史，最近的记录在前
+#[tauri::command]
+pub async fn get_interaction_history(query: Option<HistoryQuery>) -> Result<Vec<HistoryRecord>, String> {
+    let store = HistoryStore::open().map_err(|e| format!("打开交互历史失败: {}", e))?;
+    store
+        .query(&query.unwrap_or_default())
+        .map_err(|e| format!("读取交互历史失败: {}", e))
+}
+
+/// 历史中出现过的项目
+#[tauri::command]
+pub async fn get_interaction_history_projects() -> Result<Vec<String>, String> {
+    let store = HistoryStore::open().map_err(|e| format!("打开交互历史失败: {}", e))?;
+    store.projects().map_err(|e| format!("读取交互历史失败: {}", e))
+}
+
+/// 导出交互历史到下载目录，返回导出文件路径
+///
+/// `format` 为 `json` 或 `markdown`
+#[tauri::command]
+pub async fn export_interaction_history(query: Option<HistoryQuery>, format: String) -> Result<String, String> {
+    let store = HistoryStore::open().map_err(|e| format!("打开交互历史失败: {}", e))?;
+    let mut query = query.unwrap_or_default();
+    // 导出时默认不限制条数
+    query.limit = Some(query.limit.unwrap_or(usize::MAX));
+    let records = store.query(&query).map_err(|e| format!("读取交互历史失败: {}", e))?;
+
+    let (content, extension) = match format.as_str() {
+        "json" => (
+            serde_json::to_string_pretty(&records).map_err(|e| format!("序列化交互历史失败: {}", e))?,
+            "json",
+        ),
+        "markdown" | "md" => (render_markdown(&records), "md"),
+        _ => return Err(format!("不支持的导出格式: {}", format)),
+    };
+
+    let dir = dirs::download_dir()
+        .or_else(|| get_standalone_config_dir().ok())
+        .ok_or_else(|| "无法获取导出目录".to_string())?;
+    let path = dir.join(format!(
+        "cunzhi-history-{}.{}",
+        Local::now().format("%Y%m%d-%H%M%S"),
+        extension
+    ));
+    std::fs::write(&path, content).map_err(|e| format!("写入导出文件失败: {}", e))?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+fn render_markdown(records: &[HistoryRecord]) -> String {
+    let mut output = String::from("# 寸止交互历史\n");
+    for record in records {
+        output.push_str(&format!(
+            "\n## {}\n\n",
+            record.asked_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+        ));
+        if let Some(project) = &record.project_path {
+            output.push_str(&format!("- 项目: `{}`\n", project));
+        }
+        if let Some(client) = &record.request.client {
+            output.push_str(&format!("- 客户端: {}\n", client.describe()));
+        }
+        if let Some(source) = &record.response.source {
+            output.push_str(&format!("- 来源: {}\n", source));
+        }
+        output.push_str(&format!("- 耗时: {:.1} 秒\n\n", record.latency_ms as f64 / 1000.0));
+        output.push_str(&format!("**问题**\n\n{}\n\n", record.request.message.trim()));
+        output.push_str(&format!("**回答**\n\n{}\n", record.response.summary()));
+    }
+    output
+}
@@ -0,0 +1,11 @@
+//! 交互历史模块
+//!
+//! 记录每次寸止提问和回答，供 AI 检索之前的决定，也可在设置界面中浏览和导出
+
+pub mod commands;
+pub mod store;
+pub mod types;
+
+// 重新导出主要类型和功能
+pub use store::{normalize_history_project, parse_since, record_interaction, HistoryQuery, HistoryStore};
+pub use types::{HistoryOutcome, HistoryRecord, HistoryResponse, ImageMeta};
@@ -0,0 +1,317 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::types::{HistoryRecord, HistoryResponse};
+use crate::config::get_standalone_config_dir;
+use crate::mcp::types::PopupRequest;
+use crate::mcp::utils::decode_and_normalize_path;
+use crate::utils::FileLock;
+use crate::log_important;
+
+/// 交互历史文件，每行一条 JSON 记录
+const HISTORY_FILE: &str = "interaction_history.jsonl";
+
+/// 未指定数量时返回的记录数
+const DEFAULT_LIMIT: usize = 20;
+
+/// 最多保留的记录数，超出后丢弃最早的记录
+const MAX_RECORDS: usize = 2000;
+
+/// 超出上限的记录数达到该值才重写文件，避免每次追加都重写
+const TRIM_SLACK: usize = 200;
+
+/// 历史查询条件
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HistoryQuery {
+    /// 只返回该项目的记录
+    #[serde(default)]
+    pub project_path: Option<String>,
+    /// 空格分隔的关键词，命中越多越靠前
+    #[serde(default)]
+    pub keywords: Option<String>,
+    /// 只返回该时间之后的记录
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// 交互历史存储
+///
+/// 追加写入 JSON Lines 文件，多个 MCP 服务器进程可以同时写入：追加和裁剪期间持有文件锁。
+/// 记录数超过 `MAX_RECORDS` 较多时，追加后只保留最近的 `MAX_RECORDS` 条
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    /// 打开配置目录下的历史文件
+    pub fn open() -> Result<Self> {
+        Ok(Self::at(get_standalone_config_dir()?.join(HISTORY_FILE)))
+    }
+
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 追加一条记录
+    pub fn append(&self, record: &HistoryRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let _lock = FileLock::exclusive(&self.path)?;
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        drop(file);
+
+        if let Err(e) = self.trim(MAX_RECORDS, TRIM_SLACK) {
+            log_important!(warn, "裁剪交互历史失败: {}", e);
+        }
+        Ok(())
+    }
+
+    /// 记录数超过 `max_records + slack` 时只保留最近的 `max_records` 条
+    ///
+    /// 调用方需持有文件锁，避免替换文件时丢失其他进程追加的记录
+    fn trim(&self, max_records: usize, slack: usize) -> Result<()> {
+        let content = fs::read_to_string(&self.path)?;
+        let lines: Vec<&str> = content.lines().filter(|line| !line.trim().is_empty()).collect();
+        if lines.len() <= max_records + slack {
+            return Ok(());
+        }
+
+        let mut kept = lines[lines.len() - max_records..].join("\n");
+        kept.push('\n');
+        let temp = self.path.with_extension(format!("jsonl.{}.tmp", std::process::id()));
+        fs::write(&temp, kept)?;
+        if let Err(e) = fs::rename(&temp, &self.path) {
+            let _ = fs::remove_file(&temp);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// 读取全部记录，按时间先后排列；无法解析的行会被跳过
+    pub fn load(&self) -> Result<Vec<HistoryRecord>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// 按项目和关键词查询，最近的记录在前
+    ///
+    /// 有关键词时只返回至少命中一个关键词的记录，命中数多的优先
+    pub fn query(&self, query: &HistoryQuery) -> Result<Vec<HistoryRecord>> {
+        let project = query.project_path.as_deref().and_then(normalize_history_project);
+        let keywords: Vec<String> = query
+            .keywords
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+
+        let mut matches: Vec<(usize, HistoryRecord)> = self
+            .load()?
+            .into_iter()
+            .filter(|record| project.is_none() || record.project_path == project)
+            .filter(|record| query.since.is_none_or(|since| record.asked_at >= since))
+            .filter_map(|record| {
+                if keywords.is_empty() {
+                    return Some((0, record));
+                }
+                let text = record.searchable_text().to_lowercase();
+                let hits = keywords.iter().filter(|keyword| text.contains(keyword.as_str())).count();
+                (hits > 0).then_some((hits, record))
+            })
+            .collect();
+
+        matches.sort_by(|(a_hits, a), (b_hits, b)| b_hits.cmp(a_hits).then(b.asked_at.cmp(&a.asked_at)));
+
+        Ok(matches
+            .into_iter()
+            .take(query.limit.unwrap_or(DEFAULT_LIMIT))
+            .map(|(_, record)| record)
+            .collect())
+    }
+
+    /// 历史中出现过的项目
+    pub fn projects(&self) -> Result<Vec<String>> {
+        let mut projects: Vec<String> = self.load()?.into_iter().filter_map(|r| r.project_path).collect();
+        projects.sort();
+        projects.dedup();
+        Ok(projects)
+    }
+}
+
+/// 解析历史查询的起始时间
+///
+/// 支持 RFC 3339 时间、`YYYY-MM-DD` 日期（UTC 零点）和 `7d` 形式的最近天数
+pub fn parse_since(text: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if let Some(days) = text.strip_suffix('d').and_then(|days| days.parse::<i64>().ok()) {
+        return Some(now - chrono::Duration::days(days));
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|time| time.and_utc())
+}
+
+/// 规范化项目路径，位于 git 仓库中时使用仓库根目录
+pub fn normalize_history_project(project_path: &str) -> Option<String> {
+    if project_path.trim().is_empty() {
+        return None;
+    }
+    let path = PathBuf::from(decode_and_normalize_path(project_path).ok()?);
+    let path = path.canonicalize().unwrap_or(path);
+    let root = path
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(&path);
+    Some(root.to_string_lossy().to_string())
+}
+
+/// 记录一次寸止交互，失败只记录日志，不影响工具调用
+pub fn record_interaction(
+    project_path: Option<&str>,
+    request: &PopupRequest,
+    asked_at: DateTime<Utc>,
+    latency: Duration,
+    answer: &Result<String>,
+) {
+    let record = HistoryRecord {
+        id: request.id.clone(),
+        project_path: project_path.and_then(normalize_history_project),
+        asked_at,
+        latency_ms: latency.as_millis() as u64,
+        request: request.clone(),
+        response: match answer {
+            Ok(response) => HistoryResponse::from_raw(response),
+            Err(e) => HistoryResponse::failed(e.to_string()),
+        },
+    };
+
+    if let Err(e) = HistoryStore::open().and_then(|store| store.append(&record)) {
+        log_important!(warn, "保存交互历史失败: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::types::build_send_response;
+    use super::super::types::HistoryOutcome;
+
+    fn record(id: &str, project: &str, message: &str, answer: &str, minutes_ago: i64) -> HistoryRecord {
+        HistoryRecord {
+            id: id.to_string(),
+            project_path: normalize_history_project(project),
+            asked_at: Utc::now() - chrono::Duration::minutes(minutes_ago),
+            latency_ms: 1200,
+            request: serde_json::from_value(serde_json::json!({
+                "id": id,
+                "message": message,
+                "predefined_options": null,
+                "is_markdown": false,
+            }))
+            .unwrap(),
+            response: HistoryResponse::from_raw(answer),
+        }
+    }
+
+    #[test]
+    fn test_query_filters_project_and_ranks_keywords() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::at(dir.path().join(HISTORY_FILE));
+        let project_a = dir.path().join("a");
+        let project_b = dir.path().join("b");
+        fs::create_dir_all(&project_a).unwrap();
+        fs::create_dir_all(&project_b).unwrap();
+        let (a, b) = (project_a.to_str().unwrap(), project_b.to_str().unwrap());
+
+        let logging = build_send_response(None, vec!["tracing".to_string()], vec![], None, "popup");
+        store.append(&record("1", a, "日志库用哪个？", &logging, 60)).unwrap();
+        store.append(&record("2", a, "日志级别默认用什么？", "CANCELLED", 30)).unwrap();
+        store.append(&record("3", b, "日志库用哪个？", &logging, 10)).unwrap();
+        store.append(&record("4", a, "数据库迁移是否继续？", "继续", 5)).unwrap();
+
+        let results = store
+            .query(&HistoryQuery {
+                project_path: Some(a.to_string()),
+                keywords: Some("日志 tracing".to_string()),
+                since: None,
+                limit: None,
+            })
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+        assert_eq!(results[1].response.outcome, HistoryOutcome::Cancelled);
+
+        let recent = store.query(&HistoryQuery { limit: Some(2), ..Default::default() }).unwrap();
+        assert_eq!(recent.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(), vec!["4", "3"]);
+        assert_eq!(store.projects().unwrap().len(), 2);
+
+        let since = Some(Utc::now() - chrono::Duration::minutes(20));
+        let latest = store.query(&HistoryQuery { since, ..Default::default() }).unwrap();
+        assert_eq!(latest.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(), vec!["4", "3"]);
+
+        let now = Utc::now();
+        assert_eq!(parse_since("7d", now), Some(now - chrono::Duration::days(7)));
+        assert_eq!(
+            parse_since("2026-10-01", Utc::now()),
+            Some(DateTime::parse_from_rfc3339("2026-10-01T00:00:00Z").unwrap().with_timezone(&Utc))
+        );
+        assert_eq!(parse_since("last week", Utc::now()), None);
+    }
+
+    #[test]
+    fn test_load_skips_corrupt_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::at(dir.path().join(HISTORY_FILE));
+        store.append(&record("1", "", "问题", "回答", 0)).unwrap();
+        let mut file = OpenOptions::new().append(true).open(store.path()).unwrap();
+        file.write_all(b"{broken\n").unwrap();
+
+        let records = store.load().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].response.user_input.as_deref(), Some("回答"));
+        assert_eq!(records[0].project_path, None);
+    }
+
+    #[test]
+    fn test_trim_keeps_most_recent_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::at(dir.path().join(HISTORY_FILE));
+        for i in 0..6 {
+            store.append(&record(&i.to_string(), "", "问题", "回答", 10 - i)).unwrap();
+        }
+
+        store.trim(3, 3).unwrap();
+        assert_eq!(store.load().unwrap().len(), 6);
+
+        store.trim(3, 2).unwrap();
+        let ids: Vec<String> = store.load().unwrap().into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec!["3", "4", "5"]);
+    }
+}
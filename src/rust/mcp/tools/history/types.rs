@@ -0,0 +1,209 @@
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::mcp::types::{McpResponse, PopupRequest, QuestionAnswer};
+
+/// 交互结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryOutcome {
+    Answered,
+    Cancelled,
+    Failed,
+}
+
+/// 图片附件元数据，历史中不保存图片内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageMeta {
+    pub media_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// 解码后的大致字节数
+    pub size: usize,
+}
+
+/// 记录的回答
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryResponse {
+    pub outcome: HistoryOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_input: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub selected_options: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImageMeta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub form_values: Option<serde_json::Map<String, serde_json::Value>>,
+    /// 批量提问的回答，以问题ID为键
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answers: Option<BTreeMap<String, QuestionAnswer>>,
+    /// 作答来源（popup、telegram、timeout 等）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HistoryResponse {
+    /// 从渠道返回的原始响应构建
+    ///
+    /// 非结构化的文本响应（旧格式）整体作为用户输入记录
+    pub fn from_raw(raw: &str) -> Self {
+        let raw = raw.trim();
+        if raw == "CANCELLED" || raw == "用户取消了操作" {
+            return Self::empty(HistoryOutcome::Cancelled);
+        }
+
+        match serde_json::from_str::<McpResponse>(raw) {
+            Ok(response) => Self {
+                outcome: HistoryOutcome::Answered,
+                user_input: response.user_input.filter(|input| !input.trim().is_empty()),
+                selected_options: response.selected_options,
+                images: response
+                    .images
+                    .iter()
+                    .map(|image| ImageMeta {
+                        media_type: image.media_type.clone(),
+                        filename: image.filename.clone(),
+                        size: image.data.len() * 3 / 4,
+                    })
+                    .collect(),
+                form_values: response.form_values,
+                answers: response.answers,
+                source: response.metadata.source,
+                error: None,
+            },
+            Err(_) => Self {
+                user_input: Some(raw.to_string()),
+                ..Self::empty(HistoryOutcome::Answered)
+            },
+        }
+    }
+
+    /// 所有渠道均失败
+    pub fn failed(error: impl Into<String>) -> Self {
+        Self {
+            error: Some(error.into()),
+            ..Self::empty(HistoryOutcome::Failed)
+        }
+    }
+
+    fn empty(outcome: HistoryOutcome) -> Self {
+        Self {
+            outcome,
+            user_input: None,
+            selected_options: Vec::new(),
+            images: Vec::new(),
+            form_values: None,
+            answers: None,
+            source: None,
+            error: None,
+        }
+    }
+
+    /// 单行摘要
+    pub fn summary(&self) -> String {
+        match self.outcome {
+            HistoryOutcome::Cancelled => return "用户取消".to_string(),
+            HistoryOutcome::Failed => {
+                return format!("未作答（{}）", self.error.as_deref().unwrap_or("交互失败"));
+            }
+            HistoryOutcome::Answered => {}
+        }
+
+        let mut parts = Vec::new();
+        if !self.selected_options.is_empty() {
+            parts.push(format!("选项: {}", self.selected_options.join(", ")));
+        }
+        if let Some(input) = &self.user_input {
+            parts.push(format!("输入: {}", compact(input)));
+        }
+        if let Some(values) = self.form_values.as_ref().filter(|values| !values.is_empty()) {
+            let values: Vec<String> = values.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            parts.push(format!("表单: {}", values.join(", ")));
+        }
+        if let Some(answers) = self.answers.as_ref().filter(|answers| !answers.is_empty()) {
+            let answers: Vec<String> = answers
+                .iter()
+                .map(|(id, answer)| {
+                    let mut values = answer.selected_options.clone();
+                    values.extend(answer.user_input.as_deref().map(compact));
+                    format!("{}={}", id, values.join("/"))
+                })
+                .collect();
+            parts.push(format!("回答: {}", answers.join(", ")));
+        }
+        if !self.images.is_empty() {
+            parts.push(format!("图片 {} 张", self.images.len()));
+        }
+        if parts.is_empty() {
+            "（无内容）".to_string()
+        } else {
+            parts.join("; ")
+        }
+    }
+}
+
+/// 一次寸止交互
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    /// 与弹窗请求 ID 相同
+    pub id: String,
+    /// 发起调用的项目（git 根目录），调用方未提供时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_path: Option<String>,
+    pub asked_at: DateTime<Utc>,
+    /// 从提问到收到回答的耗时
+    pub latency_ms: u64,
+    pub request: PopupRequest,
+    pub response: HistoryResponse,
+}
+
+impl HistoryRecord {
+    /// 用于关键词检索的文本：问题、选项和回答
+    pub fn searchable_text(&self) -> String {
+        let mut text = self.request.message.clone();
+        for option in self.request.predefined_options.iter().flatten() {
+            text.push('\n');
+            text.push_str(&option.label);
+        }
+        for question in self.request.questions.iter().flatten() {
+            text.push('\n');
+            text.push_str(&question.message);
+        }
+        text.push('\n');
+        text.push_str(&self.response.summary());
+        text
+    }
+
+    /// 供 AI 阅读的摘要
+    pub fn format_summary(&self) -> String {
+        let mut line = format!(
+            "[{}] 问: {}\n  答: {}",
+            self.asked_at.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+            truncate(&compact(&self.request.message), 200),
+            self.response.summary()
+        );
+        if let Some(source) = &self.response.source {
+            line.push_str(&format!("（{}，{:.1} 秒）", source, self.latency_ms as f64 / 1000.0));
+        }
+        if let Some(client) = self.request.client.as_ref().and_then(|client| client.name.as_deref()) {
+            line.push_str(&format!(" [{}]", client));
+        }
+        line
+    }
+}
+
+/// 压缩空白为单个空格
+fn compact(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        format!("{}…", text.chars().take(max_chars).collect::<String>())
+    }
+}
@@ -0,0 +1,220 @@
+//! 寸止调用频率限制与循环检测
+//!
+//! 按客户端会话和项目统计窗口内的调用次数，并检测同一会话在窗口内连续发送的相同消息，
+//! 防止失控的代理反复打开交互窗口
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::config::ZhiRateLimitConfig;
+use crate::mcp::tools::history::normalize_history_project;
+
+/// 频率限制回复的来源标识
+pub const LIMIT_SOURCE: &str = "limit";
+
+/// 未携带会话标识的调用共用的会话键
+const LOCAL_SESSION: &str = "local";
+
+/// 触发的限制
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LimitViolation {
+    /// 会话在窗口内的调用次数超限
+    SessionRate { limit: u32, window_secs: u64, retry_after_secs: u64 },
+    /// 项目在窗口内的调用次数超限
+    ProjectRate { project: String, limit: u32, window_secs: u64, retry_after_secs: u64 },
+    /// 连续发送相同消息
+    RepeatedMessage { repeats: u32 },
+}
+
+impl LimitViolation {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::SessionRate { limit, window_secs, retry_after_secs } => format!(
+                "当前会话 {} 秒内的寸止调用已达上限 {} 次，请 {} 秒后再试",
+                window_secs, limit, retry_after_secs
+            ),
+            Self::ProjectRate { project, limit, window_secs, retry_after_secs } => format!(
+                "项目 {} 在 {} 秒内的寸止调用已达上限 {} 次，请 {} 秒后再试",
+                project, window_secs, limit, retry_after_secs
+            ),
+            Self::RepeatedMessage { repeats } => format!(
+                "检测到连续 {} 次发送相同的寸止消息，疑似陷入循环，请调整后再提问",
+                repeats
+            ),
+        }
+    }
+}
+
+#[derive(Default)]
+struct SessionState {
+    calls: VecDeque<Instant>,
+    last_message: Option<String>,
+    /// 上一条消息的放行时间，超出窗口后重复计数重新开始
+    last_message_at: Option<Instant>,
+    repeats: u32,
+}
+
+/// 寸止调用计数器
+///
+/// 只统计放行的调用，被拦截的调用不占用配额
+#[derive(Default)]
+pub struct ZhiLimiter {
+    sessions: HashMap<String, SessionState>,
+    projects: HashMap<String, VecDeque<Instant>>,
+}
+
+impl ZhiLimiter {
+    /// 检查一次调用，放行时计入统计
+    ///
+    /// `project` 应为 [`project_key`] 规范化后的项目路径
+    pub fn check(
+        &mut self,
+        config: &ZhiRateLimitConfig,
+        session_id: Option<&str>,
+        project: Option<&str>,
+        message: &str,
+        now: Instant,
+    ) -> Result<(), LimitViolation> {
+        if !config.enabled {
+            return Ok(());
+        }
+
+        let window = Duration::from_secs(config.window_secs);
+        self.evict_idle(now, window);
+
+        let session = self.sessions.entry(session_id.unwrap_or(LOCAL_SESSION).to_string()).or_default();
+
+        let within_window = session.last_message_at.is_some_and(|at| now.duration_since(at) < window);
+        let repeats = if within_window && session.last_message.as_deref() == Some(message) {
+            session.repeats + 1
+        } else {
+            1
+        };
+        if config.max_repeats > 0 && repeats > config.max_repeats {
+            return Err(LimitViolation::RepeatedMessage { repeats });
+        }
+
+        if config.max_per_session > 0 && session.calls.len() >= config.max_per_session as usize {
+            return Err(LimitViolation::SessionRate {
+                limit: config.max_per_session,
+                window_secs: config.window_secs,
+                retry_after_secs: retry_after(&session.calls, now, window),
+            });
+        }
+
+        if let Some(project) = project {
+            let calls = self.projects.entry(project.to_string()).or_default();
+            if config.max_per_project > 0 && calls.len() >= config.max_per_project as usize {
+                return Err(LimitViolation::ProjectRate {
+                    project: project.to_string(),
+                    limit: config.max_per_project,
+                    window_secs: config.window_secs,
+                    retry_after_secs: retry_after(calls, now, window),
+                });
+            }
+            calls.push_back(now);
+        }
+
+        session.calls.push_back(now);
+        session.last_message = Some(message.to_string());
+        session.last_message_at = Some(now);
+        session.repeats = repeats;
+        Ok(())
+    }
+
+    /// 清理过期的调用记录，并移除窗口内没有调用的会话和项目
+    fn evict_idle(&mut self, now: Instant, window: Duration) {
+        self.sessions.retain(|_, session| {
+            prune(&mut session.calls, now, window);
+            !session.calls.is_empty()
+        });
+        self.projects.retain(|_, calls| {
+            prune(calls, now, window);
+            !calls.is_empty()
+        });
+    }
+}
+
+/// 规范化项目路径作为计数键；涉及文件系统访问，应在锁定计数器之前调用
+pub fn project_key(project_path: &str) -> String {
+    normalize_history_project(project_path).unwrap_or_else(|| project_path.to_string())
+}
+
+fn prune(calls: &mut VecDeque<Instant>, now: Instant, window: Duration) {
+    while calls.front().is_some_and(|&at| now.duration_since(at) >= window) {
+        calls.pop_front();
+    }
+}
+
+fn retry_after(calls: &VecDeque<Instant>, now: Instant, window: Duration) -> u64 {
+    calls
+        .front()
+        .map(|&oldest| window.saturating_sub(now.duration_since(oldest)).as_secs().max(1))
+        .unwrap_or(0)
+}
+
+/// 进程内共享的计数器，HTTP 模式下所有会话共用，便于按项目限制
+pub fn zhi_limiter() -> &'static Mutex<ZhiLimiter> {
+    static LIMITER: OnceLock<Mutex<ZhiLimiter>> = OnceLock::new();
+    LIMITER.get_or_init(|| Mutex::new(ZhiLimiter::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ZhiRateLimitConfig {
+        ZhiRateLimitConfig {
+            enabled: true,
+            window_secs: 60,
+            max_per_session: 2,
+            max_per_project: 0,
+            max_repeats: 2,
+            canned_reply: None,
+        }
+    }
+
+    #[test]
+    fn limits_session_rate_and_repeated_messages() {
+        let config = config();
+        let mut limiter = ZhiLimiter::default();
+        let start = Instant::now();
+
+        assert!(limiter.check(&config, Some("a"), None, "继续？", start).is_ok());
+        assert!(limiter.check(&config, Some("a"), None, "继续？", start).is_ok());
+        assert_eq!(
+            limiter.check(&config, Some("a"), None, "继续？", start),
+            Err(LimitViolation::RepeatedMessage { repeats: 3 })
+        );
+        assert!(matches!(
+            limiter.check(&config, Some("a"), None, "下一步？", start),
+            Err(LimitViolation::SessionRate { retry_after_secs: 60, .. })
+        ));
+
+        // 其他会话不受影响，窗口过后恢复
+        assert!(limiter.check(&config, Some("b"), None, "继续？", start).is_ok());
+        assert!(limiter.check(&config, Some("a"), None, "下一步？", start + Duration::from_secs(60)).is_ok());
+    }
+
+    #[test]
+    fn repeats_reset_after_window_and_idle_entries_are_evicted() {
+        let config = ZhiRateLimitConfig { max_per_session: 0, max_per_project: 1, ..config() };
+        let mut limiter = ZhiLimiter::default();
+        let start = Instant::now();
+
+        assert!(limiter.check(&config, Some("a"), Some("/p"), "继续？", start).is_ok());
+        assert!(limiter.check(&config, Some("a"), None, "继续？", start + Duration::from_secs(30)).is_ok());
+        assert!(limiter.check(&config, Some("a"), None, "继续？", start + Duration::from_secs(60)).is_err());
+
+        // 上一条相同消息已超出窗口，重新计数
+        assert!(limiter.check(&config, Some("a"), None, "继续？", start + Duration::from_secs(100)).is_ok());
+        assert!(limiter.check(&config, Some("a"), None, "继续？", start + Duration::from_secs(110)).is_ok());
+
+        assert!(limiter.check(&config, Some("b"), None, "你好", start + Duration::from_secs(200)).is_ok());
+        assert_eq!(limiter.sessions.len(), 1);
+        assert!(limiter.projects.is_empty());
+    }
+}
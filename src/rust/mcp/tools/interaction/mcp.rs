@@ -1,9 +1,40 @@
 use anyhow::Result;
-use rmcp::{ErrorData as McpError, model::*};
+use rmcp::{ErrorData as McpError, RoleServer, model::*, service::Peer};
+use std::time::Instant;
 
-use crate::mcp::{ZhiRequest, PopupRequest};
-use crate::mcp::handlers::{create_tauri_popup, parse_mcp_response};
+use crate::mcp::{ClientIdentity, ZhiQuestion, ZhiRequest, PopupOption, PopupQuestion, PopupRequest, McpResponse};
+use crate::mcp::types::build_send_response;
+use crate::mcp::channels::{ask_first, select_channels};
+use crate::mcp::channels::scripted::script_file;
+use crate::mcp::handlers::{
+    format_batch_summary, format_form_summary, normalize_batch_answers, normalize_form_values, parse_mcp_response,
+};
+use crate::mcp::tools::history::record_interaction;
 use crate::mcp::utils::{generate_request_id, popup_error};
+use crate::config::{config_watcher, ZhiRateLimitConfig};
+use crate::{log_debug, log_important};
+use super::limiter::{project_key, zhi_limiter, LimitViolation, LIMIT_SOURCE};
+use super::rules::{auto_answer, match_rule};
+
+/// 交互调用上下文
+#[derive(Clone, Default)]
+pub struct InteractionContext {
+    /// 发起调用的客户端，用于 elicitation 等需要回调客户端的交互方式
+    pub peer: Option<Peer<RoleServer>>,
+    /// 客户端身份和会话标识，用于区分调用方并按会话限制调用频率
+    pub client: Option<ClientIdentity>,
+}
+
+impl InteractionContext {
+    pub fn with_peer(peer: Peer<RoleServer>) -> Self {
+        Self { peer: Some(peer), client: None }
+    }
+
+    pub fn with_client(mut self, client: ClientIdentity) -> Self {
+        self.client = Some(client);
+        self
+    }
+}
 
 /// 智能代码审查交互工具
 ///
@@ -15,21 +46,147 @@ impl InteractionTool {
     pub async fn zhi(
         request: ZhiRequest,
     ) -> Result<CallToolResult, McpError> {
+        Self::zhi_with_context(request, &InteractionContext::default()).await
+    }
+
+    /// 带调用上下文的寸止交互
+    ///
+    /// 请求同时发往所有可用的交互渠道，采用第一个回答（渠道选择见 `select_channels`）
+    pub async fn zhi_with_context(
+        request: ZhiRequest,
+        context: &InteractionContext,
+    ) -> Result<CallToolResult, McpError> {
+        let questions = Self::validate_questions(request.questions)?;
+        let message = match &questions {
+            Some(questions) if request.message.trim().is_empty() => format!("请回答以下 {} 个问题", questions.len()),
+            _ if request.message.trim().is_empty() => {
+                return Err(McpError::invalid_params("message 和 questions 不能同时为空".to_string(), None));
+            }
+            _ => request.message,
+        };
+
         let popup_request = PopupRequest {
             id: generate_request_id(),
-            message: request.message,
+            message,
             predefined_options: if request.predefined_options.is_empty() {
                 None
             } else {
-                Some(request.predefined_options)
+                Some(request.predefined_options.into_iter().map(PopupOption::from).collect())
             },
+            selection_mode: request.selection_mode,
             is_markdown: request.is_markdown,
+            timeout_seconds: request.timeout_seconds,
+            default_option: request.default_option,
+            fields: if request.fields.is_empty() {
+                None
+            } else {
+                Some(request.fields)
+            },
+            review: None,
+            client: context.client.clone(),
+            project_path: request.project_path.clone(),
+            questions,
+        };
+
+        // 自动回复规则在打开任何交互渠道之前生效；脚本作答时所有请求都交给脚本并写入记录，
+        // 不受本机规则影响
+        let config = config_watcher().current();
+        let scripted = script_file(&config.mcp_config).is_some();
+        let rules = if scripted { Vec::new() } else { config.reply_config.auto_answer_rules };
+        let rule_answer = match_rule(&rules, &popup_request, request.project_path.as_deref())
+            .and_then(|rule| auto_answer(rule, &popup_request).map(|answer| (rule, answer)));
+        if let Some((rule, answer)) = rule_answer {
+            log_important!(
+                info,
+                "寸止请求 {} 命中自动回复规则「{}」: {:?}",
+                popup_request.id,
+                rule.name,
+                rule.action
+            );
+            let mut content = parse_mcp_response(&answer)?;
+            content.push(Content::text(format!("（已按自动回复规则「{}」回答，未询问用户）", rule.name)));
+            record_interaction(
+                request.project_path.as_deref(),
+                &popup_request,
+                chrono::Utc::now(),
+                std::time::Duration::ZERO,
+                &Ok(answer),
+            );
+            return Ok(CallToolResult::success(content));
+        }
+
+        // 频率限制和循环检测，触发时不打开交互窗口；脚本作答时不会打开窗口，不做限制
+        let rate_limit = ZhiRateLimitConfig {
+            enabled: config.mcp_config.zhi_rate_limit.enabled && !scripted,
+            ..config.mcp_config.zhi_rate_limit
         };
+        let limit_project = request.project_path.as_deref().map(project_key);
+        let checked = zhi_limiter()
+            .lock()
+            .map_err(|_| popup_error("频率限制计数器不可用"))?
+            .check(
+                &rate_limit,
+                context.client.as_ref().map(|client| client.session_id.as_str()),
+                limit_project.as_deref(),
+                &Self::loop_fingerprint(&popup_request),
+                Instant::now(),
+            );
+        if let Err(violation) = checked {
+            log_important!(
+                warn,
+                "寸止请求 {} 被频率限制拦截: {}",
+                popup_request.id,
+                violation.describe()
+            );
+            return Self::limited_result(
+                &popup_request,
+                request.project_path.as_deref(),
+                &violation,
+                rate_limit.canned_reply,
+            );
+        }
+
+        let channels = select_channels(context.peer.as_ref());
+        log_debug!(
+            "寸止请求 {} 发往渠道: {:?}",
+            popup_request.id,
+            channels.iter().map(|c| c.name()).collect::<Vec<_>>()
+        );
+
+        let asked_at = chrono::Utc::now();
+        let started = Instant::now();
+        let answer = ask_first(&channels, &popup_request).await;
+        record_interaction(request.project_path.as_deref(), &popup_request, asked_at, started.elapsed(), &answer);
 
-        match create_tauri_popup(&popup_request) {
+        match answer {
             Ok(response) => {
                 // 解析响应内容，支持文本和图片
-                let content = parse_mcp_response(&response)?;
+                let mut content = parse_mcp_response(&response)?;
+                let Ok(parsed) = serde_json::from_str::<McpResponse>(&response) else {
+                    // 用户取消等非结构化响应，不返回表单结果和批量回答
+                    return Ok(CallToolResult::success(content));
+                };
+
+                // 表单请求：按字段定义整理结果，以 structured_content 返回
+                if let Some(fields) = popup_request.fields.as_deref() {
+                    let form = normalize_form_values(fields, parsed.form_values.as_ref());
+                    content.push(Content::text(format_form_summary(fields, &form)));
+
+                    let mut result = CallToolResult::success(content);
+                    result.structured_content = Some(serde_json::Value::Object(form.values));
+                    return Ok(result);
+                }
+
+                // 批量提问：按问题ID整理回答，以 structured_content 返回
+                if let Some(questions) = popup_request.questions.as_deref() {
+                    let answers = normalize_batch_answers(questions, parsed.answers.as_ref());
+                    content.push(Content::text(format_batch_summary(questions, &answers)));
+
+                    let mut result = CallToolResult::success(content);
+                    result.structured_content = Some(serde_json::json!({ "answers": answers }));
+                    return Ok(result);
+                }
+
                 Ok(CallToolResult::success(content))
             }
             Err(e) => {
@@ -37,4 +194,66 @@ impl InteractionTool {
             }
         }
     }
+
+    /// 循环检测比较的内容：消息和批量提问的全部问题
+    fn loop_fingerprint(request: &PopupRequest) -> String {
+        let mut fingerprint = request.message.clone();
+        for question in request.questions.iter().flatten() {
+            fingerprint.push('\n');
+            fingerprint.push_str(&question.message);
+        }
+        fingerprint
+    }
+
+    /// 检查批量提问的问题ID：不能为空且不能重复
+    fn validate_questions(questions: Vec<ZhiQuestion>) -> Result<Option<Vec<PopupQuestion>>, McpError> {
+        if questions.is_empty() {
+            return Ok(None);
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for question in &questions {
+            if question.id.trim().is_empty() {
+                return Err(McpError::invalid_params("questions 中的问题ID不能为空".to_string(), None));
+            }
+            if !seen.insert(question.id.as_str()) {
+                return Err(McpError::invalid_params(format!("questions 中的问题ID重复: {}", question.id), None));
+            }
+        }
+
+        Ok(Some(questions.into_iter().map(PopupQuestion::from).collect()))
+    }
+
+    /// 频率限制触发时的结果：配置了固定回复时按用户回复返回，否则返回结构化错误
+    fn limited_result(
+        popup_request: &PopupRequest,
+        project_path: Option<&str>,
+        violation: &LimitViolation,
+        canned_reply: Option<String>,
+    ) -> Result<CallToolResult, McpError> {
+        let asked_at = chrono::Utc::now();
+        let detail = serde_json::json!({
+            "error": "rate_limited",
+            "violation": violation,
+            "message": violation.describe(),
+        });
+
+        let Some(reply) = canned_reply.filter(|reply| !reply.trim().is_empty()) else {
+            let answer = Err(anyhow::anyhow!(violation.describe()));
+            record_interaction(project_path, popup_request, asked_at, std::time::Duration::ZERO, &answer);
+
+            let mut result = CallToolResult::error(vec![Content::text(violation.describe())]);
+            result.structured_content = Some(detail);
+            return Ok(result);
+        };
+
+        let answer = build_send_response(Some(reply), vec![], vec![], Some(popup_request.id.clone()), LIMIT_SOURCE);
+        let mut content = parse_mcp_response(&answer)?;
+        content.push(Content::text(format!("（{}，已使用固定回复，未询问用户）", violation.describe())));
+        record_interaction(project_path, popup_request, asked_at, std::time::Duration::ZERO, &Ok(answer));
+
+        let mut result = CallToolResult::success(content);
+        result.structured_content = Some(detail);
+        Ok(result)
+    }
 }
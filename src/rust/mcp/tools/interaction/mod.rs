@@ -2,7 +2,9 @@
 //!
 //! 提供智能代码审查交互功能，支持预定义选项、自由文本输入和图片上传
 
+pub mod limiter;
 pub mod mcp;
+pub mod rules;
 
 // 重新导出主要类型和功能
-pub use mcp::InteractionTool;
+pub use mcp::{InteractionContext, InteractionTool};
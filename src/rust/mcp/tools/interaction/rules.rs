@@ -0,0 +1,132 @@
+use regex::RegexBuilder;
+
+use crate::config::{AutoAnswerRule, RuleAction, RuleMatchType, RuleTarget};
+use crate::mcp::types::{build_continue_response, build_send_response, PopupRequest};
+use crate::mcp::tools::history::normalize_history_project;
+use crate::log_important;
+
+/// 自动回复的来源标识
+pub const RULE_SOURCE: &str = "rule";
+
+/// 按顺序查找第一条命中的启用规则
+pub fn match_rule<'a>(
+    rules: &'a [AutoAnswerRule],
+    request: &PopupRequest,
+    project_path: Option<&str>,
+) -> Option<&'a AutoAnswerRule> {
+    let project_path = project_path.map(|path| normalize_history_project(path).unwrap_or_else(|| path.to_string()));
+
+    rules.iter().filter(|rule| rule.enabled).find(|rule| {
+        let candidates: Vec<&str> = match rule.target {
+            RuleTarget::Message => vec![request.message.as_str()],
+            RuleTarget::Options => request
+                .selectable_options()
+                .iter()
+                .flat_map(|option| [option.label.as_str(), option.value.as_str()])
+                .collect(),
+            RuleTarget::ProjectPath => project_path.iter().map(String::as_str).collect(),
+        };
+        candidates.iter().any(|text| pattern_matches(rule, text))
+    })
+}
+
+fn pattern_matches(rule: &AutoAnswerRule, text: &str) -> bool {
+    match rule.match_type {
+        RuleMatchType::Substring => {
+            !rule.pattern.is_empty() && text.to_lowercase().contains(&rule.pattern.to_lowercase())
+        }
+        RuleMatchType::Regex => match RegexBuilder::new(&rule.pattern).build() {
+            Ok(regex) => regex.is_match(text),
+            Err(e) => {
+                log_important!(warn, "自动回复规则「{}」的正则表达式无效: {}", rule.name, e);
+                false
+            }
+        },
+    }
+}
+
+/// 根据规则生成自动回复，返回 `None` 时照常询问用户
+///
+/// 表单和批量提问需要逐项填写，不做自动回复；要选择的选项不存在时也照常询问
+pub fn auto_answer(rule: &AutoAnswerRule, request: &PopupRequest) -> Option<String> {
+    if request.fields.is_some() || request.questions.is_some() {
+        return None;
+    }
+
+    match &rule.action {
+        RuleAction::Ask => None,
+        RuleAction::Continue => Some(build_continue_response(Some(request.id.clone()), RULE_SOURCE)),
+        RuleAction::SelectOption { option } => {
+            let Some(selected) = request
+                .selectable_options()
+                .iter()
+                .find(|candidate| &candidate.label == option || &candidate.value == option)
+            else {
+                log_important!(warn, "自动回复规则「{}」要选择的选项不存在: {}", rule.name, option);
+                return None;
+            };
+            Some(build_send_response(
+                None,
+                vec![selected.value.clone()],
+                vec![],
+                Some(request.id.clone()),
+                RULE_SOURCE,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(target: RuleTarget, match_type: RuleMatchType, pattern: &str, action: RuleAction) -> AutoAnswerRule {
+        AutoAnswerRule {
+            name: pattern.to_string(),
+            enabled: true,
+            target,
+            match_type,
+            pattern: pattern.to_string(),
+            action,
+        }
+    }
+
+    fn request(message: &str, options: &[&str]) -> PopupRequest {
+        serde_json::from_value(serde_json::json!({
+            "id": "req",
+            "message": message,
+            "predefined_options": options,
+            "is_markdown": false,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = vec![
+            rule(RuleTarget::Message, RuleMatchType::Substring, "删除", RuleAction::Ask),
+            rule(RuleTarget::Message, RuleMatchType::Regex, r"^(Continue|继续)", RuleAction::Continue),
+            rule(
+                RuleTarget::Options,
+                RuleMatchType::Substring,
+                "yes",
+                RuleAction::SelectOption { option: "Yes".to_string() },
+            ),
+        ];
+
+        let ask = request("继续删除旧文件？", &[]);
+        assert_eq!(match_rule(&rules, &ask, None).map(|r| &r.action), Some(&RuleAction::Ask));
+        assert_eq!(auto_answer(&rules[0], &ask), None);
+
+        let next = request("Continue with the next step?", &[]);
+        let answer = auto_answer(match_rule(&rules, &next, None).unwrap(), &next).unwrap();
+        assert!(answer.contains("\"source\":\"rule\""));
+
+        let choose = request("Proceed?", &["Yes", "No"]);
+        let answer = auto_answer(match_rule(&rules, &choose, None).unwrap(), &choose).unwrap();
+        let answer: serde_json::Value = serde_json::from_str(&answer).unwrap();
+        assert_eq!(answer["selected_options"], serde_json::json!(["Yes"]));
+
+        assert!(match_rule(&rules, &request("Proceed?", &["OK"]), None).is_none());
+    }
+}
@@ -0,0 +1,20 @@
+use super::manager::MemoryManager;
+use super::organize::OrganizeReport;
+use super::types::MemoryScope;
+
+/// 整理记忆，返回整理报告
+///
+/// `scope` 为 `global` 时整理全局记忆，此时忽略 `project_path`
+#[tauri::command]
+pub async fn organize_memory(project_path: String, scope: Option<String>) -> Result<OrganizeReport, String> {
+    let scope = match scope.as_deref() {
+        Some(name) => MemoryScope::from_name(name).ok_or_else(|| format!("未知的记忆范围: {}", name))?,
+        None => MemoryScope::Project,
+    };
+    let manager = match scope {
+        MemoryScope::Project => MemoryManager::new(&project_path),
+        MemoryScope::Global => MemoryManager::global(),
+    }
+    .map_err(|e| format!("创建记忆管理器失败: {}", e))?;
+    manager.organize().map_err(|e| format!("整理记忆失败: {}", e))
+}
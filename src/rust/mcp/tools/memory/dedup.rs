@@ -0,0 +1,182 @@
+//! 记忆查重与冲突检测
+//!
+//! 添加记忆时与同一分类的已有条目比较归一化后的检索词（拉丁单词和中日韩相邻双字）：
+//! 高度相似的视为重复，主题相同但否定措辞相反的视为可能冲突
+
+use std::collections::HashSet;
+
+use super::recall::{is_cjk, tokenize};
+use super::MemoryEntry;
+
+/// 检索词集合的 Jaccard 相似度达到该值视为重复
+const DUPLICATE_JACCARD: f64 = 0.6;
+
+/// 较短一方的检索词被另一方包含的比例达到该值视为重复
+const DUPLICATE_CONTAINMENT: f64 = 0.8;
+
+/// 措辞相反时，主题检索词的包含比例达到该值视为可能冲突
+const CONFLICT_CONTAINMENT: f64 = 0.6;
+
+/// 英文否定词，作为完整单词出现时视为否定措辞
+const NEGATION_WORDS: &[&str] = &[
+    "not", "no", "never", "dont", "don't", "avoid", "without", "disable", "forbid", "forbidden", "stop",
+];
+
+/// 中文否定词，较长的在前以便优先匹配
+const CJK_NEGATION_WORDS: &[&str] = &[
+    "不要", "不再", "不能", "不得", "不用", "无需", "从不", "禁止", "禁用", "避免", "不", "别", "勿",
+];
+
+/// 以否定字开头或结尾但不表示否定的词
+const CJK_NON_NEGATION_WORDS: &[&str] = &[
+    "不同", "不仅", "不错", "不过", "不断", "不少", "不久", "不管", "不论", "不如", "不但", "差不多",
+    "别人", "别的", "区别", "特别", "分别", "类别", "级别", "识别", "告别",
+];
+
+/// 与已有记忆的比较结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum Similarity {
+    /// 内容重复；`superset` 表示新内容包含已有内容并有补充
+    Duplicate { superset: bool },
+    /// 主题相同但措辞相反
+    Conflict,
+}
+
+/// 找出与新内容重复或冲突的已有记忆
+///
+/// 调用方应只传入同一分类的记忆；重复的条目排在冲突之前
+pub fn find_similar<'a>(existing: &'a [MemoryEntry], content: &str) -> Vec<(&'a MemoryEntry, Similarity)> {
+    let new_terms = Terms::new(content);
+    if new_terms.subject.is_empty() {
+        return Vec::new();
+    }
+
+    let mut found: Vec<(&MemoryEntry, Similarity)> = existing
+        .iter()
+        .filter_map(|entry| new_terms.compare(&Terms::new(&entry.content)).map(|similarity| (entry, similarity)))
+        .collect();
+    found.sort_by_key(|(_, similarity)| matches!(similarity, Similarity::Conflict));
+    found
+}
+
+/// 归一化后的检索词
+struct Terms {
+    /// 去掉否定词后的主题检索词
+    subject: HashSet<String>,
+    negated: bool,
+}
+
+impl Terms {
+    fn new(content: &str) -> Self {
+        let lowered = content.to_lowercase();
+        let mut negated = lowered
+            .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+            .any(|token| NEGATION_WORDS.contains(&token));
+
+        // 中文否定词替换为空格，避免与前后的字组成主题检索词
+        let mut text = String::with_capacity(lowered.len());
+        let mut rest = lowered.as_str();
+        while let Some(ch) = rest.chars().next() {
+            if let Some(word) = CJK_NON_NEGATION_WORDS.iter().find(|word| rest.starts_with(*word)) {
+                text.push_str(word);
+                rest = &rest[word.len()..];
+            } else if let Some(word) = CJK_NEGATION_WORDS.iter().find(|word| rest.starts_with(*word)) {
+                negated = true;
+                text.push(' ');
+                rest = &rest[word.len()..];
+            } else {
+                text.push(ch);
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+
+        // 中日韩文本只比较相邻双字，单字过于宽泛；前后都没有中日韩字符的单字保留
+        let terms: Vec<String> = tokenize(&text)
+            .into_iter()
+            .filter(|term| !NEGATION_WORDS.contains(&term.as_str()))
+            .collect();
+        let paired: HashSet<char> = terms
+            .iter()
+            .filter(|term| term.chars().count() == 2 && term.chars().all(is_cjk))
+            .flat_map(|term| term.chars())
+            .collect();
+        let subject = terms
+            .into_iter()
+            .filter(|term| {
+                let mut chars = term.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) if is_cjk(ch) => !paired.contains(&ch),
+                    _ => true,
+                }
+            })
+            .collect();
+
+        Self { subject, negated }
+    }
+
+    fn compare(&self, other: &Terms) -> Option<Similarity> {
+        if other.subject.is_empty() {
+            return None;
+        }
+
+        let common = self.subject.intersection(&other.subject).count() as f64;
+        let union = self.subject.union(&other.subject).count() as f64;
+        let containment = common / self.subject.len().min(other.subject.len()) as f64;
+
+        if self.negated != other.negated {
+            return (containment >= CONFLICT_CONTAINMENT).then_some(Similarity::Conflict);
+        }
+
+        if common / union >= DUPLICATE_JACCARD || containment >= DUPLICATE_CONTAINMENT {
+            let superset = other.subject.is_subset(&self.subject) && self.subject.len() > other.subject.len();
+            return Some(Similarity::Duplicate { superset });
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::tools::memory::types::test_entry;
+    use crate::mcp::tools::memory::MemoryCategory;
+
+    fn entry(content: &str) -> MemoryEntry {
+        test_entry(content, content, MemoryCategory::Rule, 0)
+    }
+
+    #[test]
+    fn test_find_duplicates_and_conflicts() {
+        let existing = vec![entry("use pnpm"), entry("提交前运行 cargo fmt"), entry("数据库使用 PostgreSQL")];
+
+        let found = find_similar(&existing, "Always use pnpm for installs");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0.content, "use pnpm");
+        assert_eq!(found[0].1, Similarity::Duplicate { superset: true });
+
+        let found = find_similar(&existing, "提交前不要运行 cargo fmt");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1, Similarity::Conflict);
+
+        assert!(find_similar(&existing, "前端使用 Vue 3").is_empty());
+    }
+
+    #[test]
+    fn test_shared_cjk_words_alone_are_not_duplicates() {
+        let existing = vec![entry("使用 pnpm")];
+        assert!(find_similar(&existing, "使用 vite").is_empty());
+    }
+
+    #[test]
+    fn test_negation_compounds_are_not_negations() {
+        assert!(!Terms::new("不同平台使用不同配置").negated);
+        assert!(!Terms::new("不仅要快，效果也不错").negated);
+        assert!(Terms::new("别用 npm").negated);
+
+        let existing = vec![entry("提交前运行 cargo fmt")];
+        let found = find_similar(&existing, "不同平台提交前运行 cargo fmt");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1, Similarity::Duplicate { superset: true });
+    }
+}
@@ -1,14 +1,34 @@
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, SecondsFormat, Utc};
 use std::fs;
 use std::path::{Path, PathBuf};
-
-use super::types::{MemoryEntry, MemoryCategory, MemoryMetadata};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use super::dedup::{find_similar, Similarity};
+use super::organize::{organize, OrganizeReport};
+use super::recall::{merge_global, rank_memories, RecallQuery};
+use super::registry::register_project;
+use super::types::{AddOutcome, MemoryEntry, MemoryCategory, MemoryMetadata, MemoryScope};
+use crate::config::get_standalone_config_dir;
+use crate::utils::FileLock;
+use crate::log_important;
 
 /// 记忆管理器
+///
+/// 修改记忆文件时持有记忆目录的文件锁，多个 MCP 进程可以同时读写同一项目的记忆
 pub struct MemoryManager {
     memory_dir: PathBuf,
     project_path: String,
+    scope: MemoryScope,
+    dir_lock: Mutex<DirLock>,
+}
+
+/// 记忆目录锁的持有状态，同一管理器内的嵌套调用共用一把锁
+#[derive(Default)]
+struct DirLock {
+    depth: usize,
+    lock: Option<FileLock>,
 }
 
 impl MemoryManager {
@@ -29,11 +49,37 @@ impl MemoryManager {
         let manager = Self {
             memory_dir,
             project_path: normalized_path.to_string_lossy().to_string(),
+            scope: MemoryScope::Project,
+            dir_lock: Mutex::default(),
         };
 
         // 初始化记忆文件结构
         manager.initialize_memory_structure()?;
 
+        // 登记项目，供 MCP 资源列表使用（失败不影响记忆功能）
+        if let Err(e) = register_project(&manager.project_path) {
+            log_important!(warn, "登记记忆项目失败: {}", e);
+        }
+
+        Ok(manager)
+    }
+
+    /// 创建全局记忆管理器
+    ///
+    /// 全局记忆存放在寸止配置目录下，对所有项目生效，不要求 git 仓库
+    pub fn global() -> Result<Self> {
+        let memory_dir = get_standalone_config_dir()?.join(GLOBAL_MEMORY_DIR);
+        fs::create_dir_all(&memory_dir)
+            .map_err(|e| anyhow::anyhow!("无法创建全局记忆目录: {}\n错误: {}", memory_dir.display(), e))?;
+
+        let manager = Self {
+            project_path: memory_dir.to_string_lossy().to_string(),
+            memory_dir,
+            scope: MemoryScope::Global,
+            dir_lock: Mutex::default(),
+        };
+        manager.initialize_memory_structure()?;
+
         Ok(manager)
     }
 
@@ -168,8 +214,33 @@ impl MemoryManager {
         Ok(())
     }
 
+    /// 持有记忆目录锁执行 `f`，已持有时直接执行
+    fn with_lock<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        {
+            let mut state = self.dir_lock.lock().map_err(|_| anyhow::anyhow!("记忆目录锁不可用"))?;
+            if state.depth == 0 {
+                state.lock = Some(FileLock::exclusive(&self.memory_dir)?);
+            }
+            state.depth += 1;
+        }
+
+        let result = f();
+
+        if let Ok(mut state) = self.dir_lock.lock() {
+            state.depth -= 1;
+            if state.depth == 0 {
+                state.lock = None;
+            }
+        }
+        result
+    }
+
     /// 添加记忆条目
     pub fn add_memory(&self, content: &str, category: MemoryCategory) -> Result<String> {
+        self.with_lock(|| self.add_memory_locked(content, category))
+    }
+
+    fn add_memory_locked(&self, content: &str, category: MemoryCategory) -> Result<String> {
         let id = uuid::Uuid::new_v4().to_string();
         let now = Utc::now();
 
@@ -179,6 +250,7 @@ impl MemoryManager {
             category,
             created_at: now,
             updated_at: now,
+            scope: self.scope,
         };
 
         // 将记忆添加到对应的文件中
@@ -190,24 +262,42 @@ impl MemoryManager {
         Ok(id)
     }
 
+    /// 查重后添加记忆
+    ///
+    /// 与同一分类的已有记忆重复时不添加；新内容是已有记忆的补充时合并到该记忆，
+    /// 措辞相反的已有记忆作为可能冲突返回，由调用方决定保留哪条
+    pub fn remember(&self, content: &str, category: MemoryCategory) -> Result<AddOutcome> {
+        self.with_lock(|| self.remember_locked(content, category))
+    }
+
+    fn remember_locked(&self, content: &str, category: MemoryCategory) -> Result<AddOutcome> {
+        let existing = self.get_memories_by_category(category)?;
+        let similar = find_similar(&existing, content);
+
+        if let Some((duplicate, similarity)) = similar.iter().find(|(_, s)| matches!(s, Similarity::Duplicate { .. })) {
+            if *similarity == (Similarity::Duplicate { superset: true }) {
+                if let Some(entry) = self.update_memory(&duplicate.id, content)? {
+                    return Ok(AddOutcome::Merged { entry, previous: duplicate.content.clone() });
+                }
+            }
+            return Ok(AddOutcome::Duplicate { existing: (*duplicate).clone() });
+        }
+
+        let conflicts = similar.into_iter().map(|(entry, _)| entry.clone()).collect();
+        let id = self.add_memory(content, category)?;
+        let entry = self
+            .find_memory(&id)?
+            .ok_or_else(|| anyhow::anyhow!("新添加的记忆 {} 未能读取", id))?;
+
+        Ok(AddOutcome::Added { entry, conflicts })
+    }
+
     /// 获取所有记忆
     pub fn get_all_memories(&self) -> Result<Vec<MemoryEntry>> {
         let mut memories = Vec::new();
 
-        let categories = [
-            (MemoryCategory::Rule, "rules.md"),
-            (MemoryCategory::Preference, "preferences.md"),
-            (MemoryCategory::Pattern, "patterns.md"),
-            (MemoryCategory::Context, "context.md"),
-        ];
-
-        for (category, filename) in categories.iter() {
-            let file_path = self.memory_dir.join(filename);
-            if file_path.exists() {
-                let content = fs::read_to_string(&file_path)?;
-                let entries = self.parse_memory_file(&content, *category)?;
-                memories.extend(entries);
-            }
+        for category in MemoryCategory::ALL.iter() {
+            memories.extend(self.get_memories_by_category(*category)?);
         }
 
         // 按更新时间排序
@@ -217,80 +307,162 @@ impl MemoryManager {
     }
 
     /// 获取指定分类的记忆
+    ///
+    /// 文件中没有元数据的条目（旧版本写入或手动添加）会在读取时补上ID并写回，之后的读取ID保持不变
     pub fn get_memories_by_category(&self, category: MemoryCategory) -> Result<Vec<MemoryEntry>> {
-        let filename = match category {
-            MemoryCategory::Rule => "rules.md",
-            MemoryCategory::Preference => "preferences.md",
-            MemoryCategory::Pattern => "patterns.md",
-            MemoryCategory::Context => "context.md",
-        };
-
-        let file_path = self.memory_dir.join(filename);
+        let file_path = self.memory_dir.join(category.file_name());
         if !file_path.exists() {
             return Ok(Vec::new());
         }
 
         let content = fs::read_to_string(&file_path)?;
-        self.parse_memory_file(&content, category)
+        let (memories, has_legacy) = self.parse_memory_file(&content, category)?;
+        if has_legacy {
+            return self.rewrite_category_file(category, Some);
+        }
+
+        Ok(memories)
+    }
+
+    /// 按ID查找记忆，也接受唯一的ID前缀
+    pub fn find_memory(&self, id: &str) -> Result<Option<MemoryEntry>> {
+        let id = id.trim();
+        if id.is_empty() {
+            return Ok(None);
+        }
+
+        let memories = self.get_all_memories()?;
+        if let Some(entry) = memories.iter().find(|entry| entry.id == id) {
+            return Ok(Some(entry.clone()));
+        }
+
+        let mut matches = memories.into_iter().filter(|entry| entry.id.starts_with(id));
+        match (matches.next(), matches.next()) {
+            (Some(entry), None) => Ok(Some(entry)),
+            (Some(_), Some(_)) => Err(anyhow::anyhow!("ID 前缀 {} 匹配到多条记忆，请使用完整ID", id)),
+            _ => Ok(None),
+        }
+    }
+
+    /// 更新记忆内容，返回更新后的条目；ID不存在时返回 `None`
+    pub fn update_memory(&self, id: &str, content: &str) -> Result<Option<MemoryEntry>> {
+        self.with_lock(|| self.update_memory_locked(id, content))
+    }
+
+    fn update_memory_locked(&self, id: &str, content: &str) -> Result<Option<MemoryEntry>> {
+        let Some(existing) = self.find_memory(id)? else {
+            return Ok(None);
+        };
+
+        let mut updated = None;
+        self.rewrite_category_file(existing.category, |mut entry| {
+            if entry.id == existing.id {
+                entry.content = content.to_string();
+                entry.updated_at = Utc::now();
+                updated = Some(entry.clone());
+            }
+            Some(entry)
+        })?;
+        self.update_metadata()?;
+
+        Ok(updated)
+    }
+
+    /// 删除记忆，返回被删除的条目；ID不存在时返回 `None`
+    pub fn delete_memory(&self, id: &str) -> Result<Option<MemoryEntry>> {
+        self.with_lock(|| self.delete_memory_locked(id))
+    }
+
+    fn delete_memory_locked(&self, id: &str) -> Result<Option<MemoryEntry>> {
+        let Some(existing) = self.find_memory(id)? else {
+            return Ok(None);
+        };
+
+        self.rewrite_category_file(existing.category, |entry| (entry.id != existing.id).then_some(entry))?;
+        self.update_metadata()?;
+
+        Ok(Some(existing))
     }
 
     /// 将记忆条目添加到对应分类文件
     fn append_to_category_file(&self, entry: &MemoryEntry) -> Result<()> {
-        let filename = match entry.category {
-            MemoryCategory::Rule => "rules.md",
-            MemoryCategory::Preference => "preferences.md",
-            MemoryCategory::Pattern => "patterns.md",
-            MemoryCategory::Context => "context.md",
-        };
+        self.with_lock(|| self.append_to_category_file_locked(entry))
+    }
 
-        let file_path = self.memory_dir.join(filename);
+    fn append_to_category_file_locked(&self, entry: &MemoryEntry) -> Result<()> {
+        let file_path = self.memory_dir.join(entry.category.file_name());
         let mut content = if file_path.exists() {
             fs::read_to_string(&file_path)?
         } else {
-            format!("# {}\n\n", self.get_category_title(&entry.category))
+            self.get_category_header(&entry.category)
         };
 
-        // 简化格式：一行一个记忆
-        content.push_str(&format!("- {}\n", entry.content));
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format_entry_line(entry));
+        content.push('\n');
 
-        fs::write(&file_path, content)?;
+        write_atomic(&file_path, &content)?;
         Ok(())
     }
 
-    /// 解析记忆文件内容 - 简化版本
-    fn parse_memory_file(&self, content: &str, category: MemoryCategory) -> Result<Vec<MemoryEntry>> {
-        let mut memories = Vec::new();
+    /// 逐条改写分类文件，`edit` 返回 `None` 时删除该条目，其余内容原样保留
+    ///
+    /// 返回改写后文件中的条目
+    fn rewrite_category_file<F>(&self, category: MemoryCategory, edit: F) -> Result<Vec<MemoryEntry>>
+    where
+        F: FnMut(MemoryEntry) -> Option<MemoryEntry>,
+    {
+        self.with_lock(|| self.rewrite_category_file_locked(category, edit))
+    }
 
-        // 按列表项解析，每个 "- " 开头的行是一个记忆条目
+    fn rewrite_category_file_locked<F>(&self, category: MemoryCategory, mut edit: F) -> Result<Vec<MemoryEntry>>
+    where
+        F: FnMut(MemoryEntry) -> Option<MemoryEntry>,
+    {
+        let file_path = self.memory_dir.join(category.file_name());
+        let content = fs::read_to_string(&file_path)?;
+
+        let mut lines = Vec::new();
+        let mut memories = Vec::new();
         for line in content.lines() {
-            let line = line.trim();
-            if line.starts_with("- ") && line.len() > 2 {
-                let content = line[2..].trim(); // 去掉 "- " 前缀
-                if !content.is_empty() {
-                    let entry = MemoryEntry {
-                        id: uuid::Uuid::new_v4().to_string(),
-                        content: content.to_string(),
-                        category,
-                        created_at: Utc::now(),
-                        updated_at: Utc::now(),
-                    };
-
-                    memories.push(entry);
+            match parse_entry_line(line, category, self.scope) {
+                Some((entry, _)) => {
+                    if let Some(entry) = edit(entry) {
+                        lines.push(format_entry_line(&entry));
+                        memories.push(entry);
+                    }
                 }
+                None => lines.push(line.to_string()),
             }
         }
 
+        let mut rewritten = lines.join("\n");
+        rewritten.push('\n');
+        write_atomic(&file_path, &rewritten)?;
         Ok(memories)
     }
 
+    /// 解析记忆文件内容，返回条目以及是否存在缺少元数据的旧格式条目
+    fn parse_memory_file(&self, content: &str, category: MemoryCategory) -> Result<(Vec<MemoryEntry>, bool)> {
+        let mut memories = Vec::new();
+        let mut has_legacy = false;
+
+        // 按列表项解析，每个 "- " 开头的行是一个记忆条目
+        for line in content.lines() {
+            if let Some((entry, legacy)) = parse_entry_line(line, category, self.scope) {
+                has_legacy |= legacy;
+                memories.push(entry);
+            }
+        }
+
+        Ok((memories, has_legacy))
+    }
+
     /// 获取分类标题
     fn get_category_title(&self, category: &MemoryCategory) -> &str {
-        match category {
-            MemoryCategory::Rule => "开发规范和规则",
-            MemoryCategory::Preference => "用户偏好设置",
-            MemoryCategory::Pattern => "常用模式和最佳实践",
-            MemoryCategory::Context => "项目上下文信息",
-        }
+        category.title()
     }
 
     /// 获取分类文件头部（简化版本）
@@ -298,65 +470,287 @@ impl MemoryManager {
         format!("# {}\n\n", self.get_category_title(category))
     }
 
-    /// 更新元数据
+    /// 更新元数据，保留上次整理时间
     fn update_metadata(&self) -> Result<()> {
+        let last_organized = self.read_metadata().and_then(|metadata| metadata.last_organized);
+        self.write_metadata(last_organized, self.get_all_memories()?.len())
+    }
+
+    fn read_metadata(&self) -> Option<MemoryMetadata> {
+        let content = fs::read_to_string(self.memory_dir.join("metadata.json")).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn write_metadata(&self, last_organized: Option<DateTime<Utc>>, total_entries: usize) -> Result<()> {
+        let (metadata_path, metadata_json) = self.render_metadata(last_organized, total_entries)?;
+        write_atomic(&metadata_path, &metadata_json)?;
+
+        Ok(())
+    }
+
+    /// 生成元数据文件路径和内容
+    fn render_metadata(&self, last_organized: Option<DateTime<Utc>>, total_entries: usize) -> Result<(PathBuf, String)> {
         let metadata = MemoryMetadata {
             project_path: self.project_path.clone(),
-            last_organized: Utc::now(),
-            total_entries: self.get_all_memories()?.len(),
+            last_organized,
+            total_entries,
             version: "1.0.0".to_string(),
         };
 
-        let metadata_path = self.memory_dir.join("metadata.json");
-        let metadata_json = serde_json::to_string_pretty(&metadata)?;
-        fs::write(metadata_path, metadata_json)?;
+        Ok((self.memory_dir.join("metadata.json"), serde_json::to_string_pretty(&metadata)?))
+    }
 
-        Ok(())
+    /// 整理记忆：归档过时条目、调整分类、去重并排序，然后重写分类文件和元数据
+    ///
+    /// 分类文件按标题和条目重新生成，条目以外的手写内容不会保留
+    pub fn organize(&self) -> Result<OrganizeReport> {
+        self.with_lock(|| self.organize_locked())
     }
 
-    /// 获取项目信息供MCP调用方分析 - 压缩简化版本
-    pub fn get_project_info(&self) -> Result<String> {
-        // 汇总所有记忆规则并压缩
-        let all_memories = self.get_all_memories()?;
-        if all_memories.is_empty() {
-            return Ok("📭 暂无项目记忆".to_string());
+    fn organize_locked(&self) -> Result<OrganizeReport> {
+        let now = Utc::now();
+        let (kept, report) = organize(self.get_all_memories()?, now);
+
+        let mut files = Vec::new();
+        for category in MemoryCategory::ALL.iter() {
+            let mut content = self.get_category_header(category);
+            for entry in kept.iter().filter(|entry| entry.category == *category) {
+                content.push_str(&format_entry_line(entry));
+                content.push('\n');
+            }
+            files.push((self.memory_dir.join(category.file_name()), content));
         }
 
-        let mut compressed_info = Vec::new();
+        if !report.archived.is_empty() {
+            let archive_path = self.memory_dir.join(ARCHIVE_FILE);
+            let mut content = fs::read_to_string(&archive_path).unwrap_or_else(|_| "# 已归档记忆\n\n".to_string());
+            if !content.ends_with('\n') {
+                content.push('\n');
+            }
+            for entry in &report.archived {
+                content.push_str(&format_entry_line(entry));
+                content.push('\n');
+            }
+            files.push((archive_path, content));
+        }
 
-        // 按分类压缩汇总
-        let categories = [
-            (MemoryCategory::Rule, "规范"),
-            (MemoryCategory::Preference, "偏好"),
-            (MemoryCategory::Pattern, "模式"),
-            (MemoryCategory::Context, "背景"),
-        ];
+        files.push(self.render_metadata(Some(now), report.total_after)?);
+        replace_files(files)?;
 
-        for (category, title) in categories.iter() {
-            let memories = self.get_memories_by_category(*category)?;
-            if !memories.is_empty() {
-                let mut items = Vec::new();
-                for memory in memories {
-                    let content = memory.content.trim();
-                    if !content.is_empty() {
-                        // 去除多余空格和换行，压缩内容
-                        let compressed_content = content
-                            .split_whitespace()
-                            .collect::<Vec<&str>>()
-                            .join(" ");
-                        items.push(compressed_content);
-                    }
-                }
-                if !items.is_empty() {
-                    compressed_info.push(format!("**{}**: {}", title, items.join("; ")));
-                }
+        Ok(report)
+    }
+
+    /// 按条件回忆记忆供MCP调用方分析
+    ///
+    /// 按相关度排序后逐条输出，超出 `max_chars` 的条目被省略
+    pub fn recall(&self, query: &RecallQuery) -> Result<String> {
+        format_recall(self.get_all_memories()?, query, self.scope.title())
+    }
+
+    /// 合并全局记忆后回忆，项目记忆覆盖与之重复或冲突的全局记忆
+    pub fn recall_with_global(&self, global: &MemoryManager, query: &RecallQuery) -> Result<String> {
+        let memories = merge_global(self.get_all_memories()?, global.get_all_memories()?);
+        format_recall(memories, query, "项目与全局")
+    }
+}
+
+/// 按相关度排序并在字符预算内输出记忆
+fn format_recall(memories: Vec<MemoryEntry>, query: &RecallQuery, scope_title: &str) -> Result<String> {
+    if memories.is_empty() {
+        return Ok(format!("📭 暂无{}记忆", scope_title));
+    }
+
+    let ranked = rank_memories(memories, query);
+    if ranked.is_empty() {
+        return Ok(format!("📭 没有找到相关的{}记忆", scope_title));
+    }
+
+    let mut lines = Vec::new();
+    let mut used_chars = 0;
+    for memory in &ranked {
+        // 去除多余空格和换行，压缩内容
+        let content = memory.content.split_whitespace().collect::<Vec<&str>>().join(" ");
+        let marker = if memory.scope == MemoryScope::Global { "🌐 " } else { "" };
+        let line = format!("- {}**{}** [{}] {}", marker, memory.category.short_title(), memory.short_id(), content);
+        let line_chars = line.chars().count() + 1;
+        if used_chars + line_chars > query.max_chars && !lines.is_empty() {
+            break;
+        }
+        used_chars += line_chars;
+        lines.push(line);
+    }
+
+    let mut info = format!("📚 {}记忆（显示 {}/{} 条）:\n{}", scope_title, lines.len(), ranked.len(), lines.join("\n"));
+    if ranked.iter().any(|memory| memory.scope == MemoryScope::Global) {
+        info.push_str("\n（🌐 为全局记忆）");
+    }
+    if lines.len() < ranked.len() {
+        info.push_str(&format!(
+            "\n…另有 {} 条因长度限制未显示，可通过 query 或 categories 缩小范围",
+            ranked.len() - lines.len()
+        ));
+    }
+    Ok(info)
+}
+
+/// 全局记忆在配置目录下的子目录名
+const GLOBAL_MEMORY_DIR: &str = "memory";
+
+/// 归档的过时记忆文件名
+const ARCHIVE_FILE: &str = "archive.md";
+
+/// 先写入临时文件再替换，避免中途失败留下不完整的文件
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let temp_path = staging_path(path);
+    if let Err(e) = fs::write(&temp_path, content).and_then(|_| fs::rename(&temp_path, path)) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// 替换一组文件
+///
+/// 先全部写入临时文件，全部成功后再逐个替换；写入失败时清理临时文件，
+/// 替换中途失败时尽量恢复已替换的文件，避免留下一半新一半旧的记忆
+fn replace_files(files: Vec<(PathBuf, String)>) -> Result<()> {
+    let mut staged = Vec::new();
+    for (path, content) in files {
+        let temp_path = staging_path(&path);
+        if let Err(e) = fs::write(&temp_path, content) {
+            let _ = fs::remove_file(&temp_path);
+            for (temp_path, _, _) in &staged {
+                let _ = fs::remove_file(temp_path);
             }
+            return Err(e.into());
         }
+        let original = fs::read_to_string(&path).ok();
+        staged.push((temp_path, path, original));
+    }
 
-        if compressed_info.is_empty() {
-            Ok("📭 暂无有效项目记忆".to_string())
-        } else {
-            Ok(format!("📚 项目记忆总览: {}", compressed_info.join(" | ")))
+    for (index, (temp_path, path, _)) in staged.iter().enumerate() {
+        let Err(e) = fs::rename(temp_path, path) else {
+            continue;
+        };
+
+        for (temp_path, _, _) in &staged[index..] {
+            let _ = fs::remove_file(temp_path);
+        }
+        for (_, path, original) in &staged[..index] {
+            let restored = match original {
+                Some(content) => write_atomic(path, content),
+                None => fs::remove_file(path).map_err(Into::into),
+            };
+            if let Err(restore_error) = restored {
+                log_important!(warn, "恢复记忆文件 {} 失败: {}", path.display(), restore_error);
+            }
+        }
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+/// 暂存文件路径，与原文件位于同一目录以便原子替换
+///
+/// 文件名包含进程ID和序号，不同进程和线程的写入互不覆盖
+fn staging_path(path: &Path) -> PathBuf {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".{}.{}.tmp", std::process::id(), SEQUENCE.fetch_add(1, Ordering::Relaxed)));
+    path.with_file_name(file_name)
+}
+
+/// 记忆条目行尾的元数据注释前缀
+const ENTRY_META_PREFIX: &str = "<!--";
+
+/// 将记忆条目格式化为一行：`- 内容 <!-- id=... created=... updated=... -->`
+///
+/// 元数据放在 HTML 注释中，渲染 markdown 时不可见
+fn format_entry_line(entry: &MemoryEntry) -> String {
+    let content = entry.content.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!(
+        "- {} {} id={} created={} updated={} -->",
+        content,
+        ENTRY_META_PREFIX,
+        entry.id,
+        entry.created_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        entry.updated_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+    )
+}
+
+/// 解析一行记忆条目，第二个返回值表示该行缺少元数据（需要补写）
+fn parse_entry_line(line: &str, category: MemoryCategory, scope: MemoryScope) -> Option<(MemoryEntry, bool)> {
+    let line = line.trim();
+    let body = line.strip_prefix("- ")?.trim();
+
+    let (content, meta) = match body.rfind(ENTRY_META_PREFIX) {
+        Some(index) if body.ends_with("-->") => (
+            body[..index].trim(),
+            parse_entry_meta(&body[index + ENTRY_META_PREFIX.len()..body.len() - 3]),
+        ),
+        _ => (body, None),
+    };
+    if content.is_empty() {
+        return None;
+    }
+
+    let legacy = meta.is_none();
+    let (id, created_at, updated_at) = meta.unwrap_or_else(|| {
+        let now = Utc::now();
+        (uuid::Uuid::new_v4().to_string(), now, now)
+    });
+
+    Some((
+        MemoryEntry {
+            id,
+            content: content.to_string(),
+            category,
+            created_at,
+            updated_at,
+            scope,
+        },
+        legacy,
+    ))
+}
+
+fn parse_entry_meta(meta: &str) -> Option<(String, DateTime<Utc>, DateTime<Utc>)> {
+    let mut id = None;
+    let mut created_at = None;
+    let mut updated_at = None;
+
+    for pair in meta.split_whitespace() {
+        let Some((key, value)) = pair.split_once('=') else {
+            continue;
+        };
+        match key {
+            "id" => id = Some(value.to_string()),
+            "created" => created_at = DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc)),
+            "updated" => updated_at = DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc)),
+            _ => {}
         }
     }
+
+    let created_at = created_at?;
+    Some((id?, created_at, updated_at.unwrap_or(created_at)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_line_round_trip() {
+        let (legacy, needs_meta) = parse_entry_line("- 使用 pnpm 安装依赖", MemoryCategory::Rule, MemoryScope::Project).unwrap();
+        assert!(needs_meta);
+
+        let line = format_entry_line(&legacy);
+        let (parsed, needs_meta) = parse_entry_line(&line, MemoryCategory::Rule, MemoryScope::Project).unwrap();
+        assert!(!needs_meta);
+        assert_eq!(parsed.id, legacy.id);
+        assert_eq!(parsed.content, "使用 pnpm 安装依赖");
+        assert_eq!(parsed.created_at.timestamp(), legacy.created_at.timestamp());
+
+        assert!(parse_entry_line("# 开发规范和规则", MemoryCategory::Rule, MemoryScope::Project).is_none());
+    }
 }
@@ -1,8 +1,11 @@
 use anyhow::Result;
 use rmcp::{ErrorData as McpError, model::*};
 
-use super::{MemoryManager, MemoryCategory};
+use super::{AddOutcome, MemoryEntry, MemoryManager, MemoryCategory, MemoryScope, RecallQuery};
+use crate::constants::mcp::DEFAULT_MEMORY_RECALL_MAX_CHARS;
 use crate::mcp::{JiyiRequest, utils::{validate_project_path, project_path_error}};
+use crate::mcp::tools::history::{parse_since, HistoryQuery, HistoryStore};
+use crate::log_important;
 
 /// 全局记忆管理工具
 ///
@@ -23,8 +26,18 @@ impl MemoryTool {
             )).into());
         }
 
-        let manager = MemoryManager::new(&request.project_path)
-            .map_err(|e| McpError::internal_error(format!("创建记忆管理器失败: {}", e), None))?;
+        // 交互历史不依赖记忆目录
+        if request.action == "历史" {
+            return Self::search_history(&request);
+        }
+
+        let scope = MemoryScope::from_name(&request.scope)
+            .ok_or_else(|| McpError::invalid_params(format!("未知的记忆范围: {}", request.scope), None))?;
+        let manager = match scope {
+            MemoryScope::Project => MemoryManager::new(&request.project_path),
+            MemoryScope::Global => MemoryManager::global(),
+        }
+        .map_err(|e| McpError::internal_error(format!("创建记忆管理器失败: {}", e), None))?;
 
         let result = match request.action.as_str() {
             "记忆" => {
@@ -32,22 +45,53 @@ impl MemoryTool {
                     return Err(McpError::invalid_params("缺少记忆内容".to_string(), None));
                 }
 
-                let category = match request.category.as_str() {
-                    "rule" => MemoryCategory::Rule,
-                    "preference" => MemoryCategory::Preference,
-                    "pattern" => MemoryCategory::Pattern,
-                    "context" => MemoryCategory::Context,
-                    _ => MemoryCategory::Context,
-                };
+                let category = MemoryCategory::from_name(&request.category).unwrap_or(MemoryCategory::Context);
 
-                let id = manager.add_memory(&request.content, category)
+                let outcome = manager.remember(&request.content, category)
                     .map_err(|e| McpError::internal_error(format!("添加记忆失败: {}", e), None))?;
 
-                format!("✅ 记忆已添加，ID: {}\n📝 内容: {}\n📂 分类: {:?}", id, request.content, category)
+                Self::format_add_outcome(outcome)
+            }
+            "更新" => {
+                if request.content.trim().is_empty() {
+                    return Err(McpError::invalid_params("缺少更新后的记忆内容".to_string(), None));
+                }
+                let id = Self::require_id(&request)?;
+
+                let entry = manager.update_memory(id, &request.content)
+                    .map_err(|e| McpError::internal_error(format!("更新记忆失败: {}", e), None))?
+                    .ok_or_else(|| Self::not_found(id, scope))?;
+
+                format!("✅ 记忆已更新，ID: {}\n📝 内容: {}\n{}", entry.id, entry.content, Self::describe_location(&entry))
+            }
+            "删除" => {
+                let id = Self::require_id(&request)?;
+
+                let entry = manager.delete_memory(id)
+                    .map_err(|e| McpError::internal_error(format!("删除记忆失败: {}", e), None))?
+                    .ok_or_else(|| Self::not_found(id, scope))?;
+
+                format!("🗑️ 记忆已删除，ID: {}\n📝 内容: {}\n{}", entry.id, entry.content, Self::describe_location(&entry))
+            }
+            "整理" => {
+                manager.organize()
+                    .map_err(|e| McpError::internal_error(format!("整理记忆失败: {}", e), None))?
+                    .describe()
             }
             "回忆" => {
-                manager.get_project_info()
-                    .map_err(|e| McpError::internal_error(format!("获取项目信息失败: {}", e), None))?
+                let query = Self::recall_query(&request)?;
+                // 项目范围的回忆合并全局记忆，全局记忆不可用时只返回项目记忆
+                let global = match scope {
+                    MemoryScope::Project => MemoryManager::global()
+                        .map_err(|e| log_important!(warn, "读取全局记忆失败: {}", e))
+                        .ok(),
+                    MemoryScope::Global => None,
+                };
+                match &global {
+                    Some(global) => manager.recall_with_global(global, &query),
+                    None => manager.recall(&query),
+                }
+                .map_err(|e| McpError::internal_error(format!("获取项目信息失败: {}", e), None))?
             }
             _ => {
                 return Err(McpError::invalid_params(
@@ -59,4 +103,105 @@ impl MemoryTool {
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
+
+    fn format_add_outcome(outcome: AddOutcome) -> String {
+        match outcome {
+            AddOutcome::Added { entry, conflicts } => {
+                let mut text = format!("✅ 记忆已添加，ID: {}\n📝 内容: {}\n{}", entry.id, entry.content, Self::describe_location(&entry));
+                if !conflicts.is_empty() {
+                    text.push_str("\n⚠️ 可能与以下已有记忆冲突:");
+                    for conflict in &conflicts {
+                        text.push_str(&format!("\n- [{}] {}", conflict.short_id(), conflict.content));
+                    }
+                    text.push_str("\n请通过 zhi 询问用户以哪条为准，再使用删除或更新操作处理其余记忆");
+                }
+                text
+            }
+            AddOutcome::Merged { entry, previous } => format!(
+                "🔀 与已有记忆重复，已合并，ID: {}\n📝 原内容: {}\n📝 新内容: {}\n{}",
+                entry.id, previous, entry.content, Self::describe_location(&entry)
+            ),
+            AddOutcome::Duplicate { existing } => format!(
+                "⏭️ 与已有记忆重复，未添加，已有记忆ID: {}\n📝 内容: {}\n如需修改请使用更新操作",
+                existing.id, existing.content
+            ),
+        }
+    }
+
+    fn describe_location(entry: &MemoryEntry) -> String {
+        format!("📂 分类: {:?}（{}记忆）", entry.category, entry.scope.title())
+    }
+
+    fn recall_query(request: &JiyiRequest) -> Result<RecallQuery, McpError> {
+        let categories = request
+            .categories
+            .iter()
+            .map(|name| {
+                MemoryCategory::from_name(name)
+                    .ok_or_else(|| McpError::invalid_params(format!("未知的记忆分类: {}", name), None))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(RecallQuery {
+            query: request.query.clone(),
+            categories,
+            max_chars: request.max_chars.unwrap_or(DEFAULT_MEMORY_RECALL_MAX_CHARS),
+        })
+    }
+
+    fn require_id(request: &JiyiRequest) -> Result<&str, McpError> {
+        let id = request.id.trim();
+        if id.is_empty() {
+            return Err(McpError::invalid_params(format!("{}操作缺少记忆ID", request.action), None));
+        }
+        Ok(id)
+    }
+
+    /// 更新和删除只在请求的范围内查找，全局记忆需要显式指定 `scope: "global"`
+    fn not_found(id: &str, scope: MemoryScope) -> McpError {
+        let hint = match scope {
+            MemoryScope::Project => "；回忆结果中标记 🌐 的全局记忆需指定 scope 为 global",
+            MemoryScope::Global => "",
+        };
+        McpError::invalid_params(
+            format!("未找到ID为 {} 的{}记忆，请先通过回忆操作查看记忆ID{}", id, scope.title(), hint),
+            None,
+        )
+    }
+
+    /// 检索当前项目之前的寸止交互
+    fn search_history(request: &JiyiRequest) -> Result<CallToolResult, McpError> {
+        let store = HistoryStore::open()
+            .map_err(|e| McpError::internal_error(format!("打开交互历史失败: {}", e), None))?;
+        // 关键词优先取 query，兼容早期放在 content 中的写法
+        let keywords = request
+            .query
+            .as_deref()
+            .map(str::trim)
+            .filter(|query| !query.is_empty())
+            .unwrap_or_else(|| request.content.trim());
+        let since = match request.since.as_deref().map(str::trim).filter(|since| !since.is_empty()) {
+            Some(text) => Some(parse_since(text, chrono::Utc::now()).ok_or_else(|| {
+                McpError::invalid_params(format!("无法解析历史起始时间: {}", text), None)
+            })?),
+            None => None,
+        };
+        let records = store
+            .query(&HistoryQuery {
+                project_path: Some(request.project_path.clone()),
+                keywords: (!keywords.is_empty()).then(|| keywords.to_string()),
+                since,
+                limit: None,
+            })
+            .map_err(|e| McpError::internal_error(format!("读取交互历史失败: {}", e), None))?;
+
+        let text = if records.is_empty() {
+            "📭 没有找到相关的交互历史".to_string()
+        } else {
+            let items: Vec<String> = records.iter().map(|record| record.format_summary()).collect();
+            format!("🕘 找到 {} 条交互历史:\n{}", records.len(), items.join("\n"))
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
 }
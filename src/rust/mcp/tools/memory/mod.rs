@@ -2,11 +2,20 @@
 //!
 //! 提供全局记忆管理功能，用于存储和管理重要的开发规范、用户偏好和最佳实践
 
+pub mod commands;
+pub mod dedup;
 pub mod manager;
 pub mod types;
 pub mod mcp;
+pub mod organize;
+pub mod recall;
+pub mod registry;
+pub mod resources;
 
 // 重新导出主要类型和功能
 pub use manager::MemoryManager;
-pub use types::{MemoryEntry, MemoryCategory, MemoryMetadata};
+pub use types::{AddOutcome, MemoryEntry, MemoryCategory, MemoryMetadata, MemoryScope};
 pub use mcp::MemoryTool;
+pub use organize::OrganizeReport;
+pub use recall::RecallQuery;
+pub use resources::MemoryResources;
@@ -0,0 +1,273 @@
+//! 记忆整理
+//!
+//! 归档标记为过时的条目，按关键词把放错分类的条目移到合适的分类，
+//! 去除重复条目并按创建时间排序
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::dedup::{find_similar, Similarity};
+use super::{MemoryCategory, MemoryEntry};
+
+/// 过时标记，条目内容包含任一标记时归档
+const OBSOLETE_MARKERS: &[&str] = &["[obsolete]", "[deprecated]", "[过时]", "[已过时]", "[废弃]", "[已废弃]"];
+
+/// 各分类的特征关键词，用于判断条目是否放错分类
+const CATEGORY_KEYWORDS: &[(MemoryCategory, &[&str])] = &[
+    (
+        MemoryCategory::Rule,
+        &["必须", "禁止", "不得", "不允许", "规范", "规则", "must", "never", "always", "rule"],
+    ),
+    (
+        MemoryCategory::Preference,
+        &["喜欢", "偏好", "偏爱", "习惯", "倾向", "希望", "prefer", "preference", "like"],
+    ),
+    (
+        MemoryCategory::Pattern,
+        &["模式", "最佳实践", "惯用", "做法", "示例", "pattern", "best practice", "idiom"],
+    ),
+    (
+        MemoryCategory::Context,
+        &["项目使用", "技术栈", "架构", "目录结构", "版本", "部署", "stack", "architecture", "deployed"],
+    ),
+];
+
+/// 条目至少命中该数量的目标分类关键词（且不命中当前分类关键词）才会移动
+const MOVE_MIN_HITS: usize = 2;
+
+/// 被去重的条目
+#[derive(Debug, Clone, Serialize)]
+pub struct RemovedDuplicate {
+    pub entry: MemoryEntry,
+    /// 保留的条目ID
+    pub kept_id: String,
+    /// 被去重条目的内容是否合并到了保留的条目
+    pub merged: bool,
+}
+
+/// 移动分类的条目
+#[derive(Debug, Clone, Serialize)]
+pub struct MovedEntry {
+    pub entry: MemoryEntry,
+    pub from: MemoryCategory,
+}
+
+/// 整理报告
+#[derive(Debug, Clone, Serialize)]
+pub struct OrganizeReport {
+    pub organized_at: DateTime<Utc>,
+    pub total_before: usize,
+    pub total_after: usize,
+    pub archived: Vec<MemoryEntry>,
+    pub moved: Vec<MovedEntry>,
+    pub removed: Vec<RemovedDuplicate>,
+}
+
+impl OrganizeReport {
+    pub fn is_unchanged(&self) -> bool {
+        self.archived.is_empty() && self.moved.is_empty() && self.removed.is_empty()
+    }
+
+    /// 生成文本报告
+    pub fn describe(&self) -> String {
+        let mut lines = vec![format!("🧹 记忆整理完成: {} 条 → {} 条", self.total_before, self.total_after)];
+        if self.is_unchanged() {
+            lines.push("没有需要整理的记忆，已按创建时间重新排序".to_string());
+            return lines.join("\n");
+        }
+
+        if !self.archived.is_empty() {
+            lines.push(format!("📦 归档过时记忆 {} 条:", self.archived.len()));
+            for entry in &self.archived {
+                lines.push(format!("- [{}] {}", entry.short_id(), entry.content));
+            }
+        }
+        if !self.moved.is_empty() {
+            lines.push(format!("📂 调整分类 {} 条:", self.moved.len()));
+            for moved in &self.moved {
+                lines.push(format!(
+                    "- [{}] {}（{} → {}）",
+                    moved.entry.short_id(),
+                    moved.entry.content,
+                    moved.from.short_title(),
+                    moved.entry.category.short_title()
+                ));
+            }
+        }
+        if !self.removed.is_empty() {
+            lines.push(format!("🔀 去除重复记忆 {} 条:", self.removed.len()));
+            for removed in &self.removed {
+                let action = if removed.merged { "内容已合并到" } else { "保留" };
+                lines.push(format!(
+                    "- [{}] {}（{} {}）",
+                    removed.entry.short_id(),
+                    removed.entry.content,
+                    action,
+                    removed.kept_id.get(..8).unwrap_or(&removed.kept_id)
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+/// 整理记忆，返回保留的条目（按分类、创建时间排序）和整理报告
+pub fn organize(entries: Vec<MemoryEntry>, now: DateTime<Utc>) -> (Vec<MemoryEntry>, OrganizeReport) {
+    let mut report = OrganizeReport {
+        organized_at: now,
+        total_before: entries.len(),
+        total_after: 0,
+        archived: Vec::new(),
+        moved: Vec::new(),
+        removed: Vec::new(),
+    };
+
+    let mut active = Vec::new();
+    // 调整了分类的条目ID及原分类，去重后只报告保留下来的条目
+    let mut moved_from = HashMap::new();
+    for mut entry in entries {
+        if is_obsolete(&entry.content) {
+            report.archived.push(entry);
+            continue;
+        }
+
+        if let Some(category) = suggest_category(&entry) {
+            moved_from.insert(entry.id.clone(), entry.category);
+            entry.category = category;
+            entry.updated_at = now;
+        }
+        active.push(entry);
+    }
+
+    // 先创建的条目优先保留
+    active.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    let mut kept: Vec<MemoryEntry> = Vec::new();
+    for entry in active {
+        let same_category: Vec<MemoryEntry> =
+            kept.iter().filter(|kept| kept.category == entry.category).cloned().collect();
+        let duplicate = find_similar(&same_category, &entry.content)
+            .into_iter()
+            .find_map(|(existing, similarity)| match similarity {
+                Similarity::Duplicate { superset } => Some((existing.id.clone(), superset)),
+                Similarity::Conflict => None,
+            });
+
+        let Some((kept_id, superset)) = duplicate else {
+            kept.push(entry);
+            continue;
+        };
+
+        if superset {
+            if let Some(target) = kept.iter_mut().find(|kept| kept.id == kept_id) {
+                target.content = entry.content.clone();
+                target.updated_at = now;
+            }
+        }
+        report.removed.push(RemovedDuplicate { entry, kept_id, merged: superset });
+    }
+
+    kept.sort_by(|a, b| {
+        let position = |category: &MemoryCategory| MemoryCategory::ALL.iter().position(|c| c == category);
+        position(&a.category)
+            .cmp(&position(&b.category))
+            .then(a.created_at.cmp(&b.created_at))
+    });
+    report.moved = kept
+        .iter()
+        .filter_map(|entry| moved_from.get(&entry.id).map(|&from| MovedEntry { entry: entry.clone(), from }))
+        .collect();
+    report.total_after = kept.len();
+
+    (kept, report)
+}
+
+fn is_obsolete(content: &str) -> bool {
+    let content = content.trim();
+    let lowered = content.to_lowercase();
+    OBSOLETE_MARKERS.iter().any(|marker| lowered.contains(marker))
+        || (content.len() > 4 && content.starts_with("~~") && content.ends_with("~~"))
+}
+
+/// 根据关键词推断条目应属的分类，无需移动时返回 `None`
+fn suggest_category(entry: &MemoryEntry) -> Option<MemoryCategory> {
+    let lowered = entry.content.to_lowercase();
+    let hits = |keywords: &[&str]| keywords.iter().filter(|keyword| contains_keyword(&lowered, keyword)).count();
+
+    let current_hits = CATEGORY_KEYWORDS
+        .iter()
+        .find(|(category, _)| *category == entry.category)
+        .map_or(0, |(_, keywords)| hits(keywords));
+    if current_hits > 0 {
+        return None;
+    }
+
+    CATEGORY_KEYWORDS
+        .iter()
+        .map(|(category, keywords)| (*category, hits(keywords)))
+        .filter(|(_, count)| *count >= MOVE_MIN_HITS)
+        .max_by_key(|(_, count)| *count)
+        .map(|(category, _)| category)
+}
+
+/// 判断文本是否包含关键词；英文关键词必须是完整单词，避免 `like` 命中 `likely`
+fn contains_keyword(text: &str, keyword: &str) -> bool {
+    if !keyword.is_ascii() {
+        return text.contains(keyword);
+    }
+
+    let is_word_char = |ch: char| ch.is_ascii_alphanumeric() || ch == '_';
+    text.match_indices(keyword).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + keyword.len()..].chars().next();
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::tools::memory::types::test_entry as entry;
+
+    #[test]
+    fn test_organize_archives_moves_and_dedups() {
+        let entries = vec![
+            entry("a", "use pnpm", MemoryCategory::Rule, 3),
+            entry("b", "always use pnpm for installs", MemoryCategory::Rule, 1),
+            entry("c", "[过时] 使用 webpack 打包", MemoryCategory::Context, 5),
+            entry("d", "提交前必须运行 cargo fmt，禁止跳过", MemoryCategory::Context, 2),
+        ];
+
+        let (kept, report) = organize(entries, Utc::now());
+
+        assert_eq!(report.total_before, 4);
+        assert_eq!(report.archived.len(), 1);
+        assert_eq!(report.moved.len(), 1);
+        assert_eq!(report.moved[0].entry.category, MemoryCategory::Rule);
+        assert_eq!(report.removed.len(), 1);
+        assert!(report.removed[0].merged);
+
+        let contents: Vec<&str> = kept.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, vec!["always use pnpm for installs", "提交前必须运行 cargo fmt，禁止跳过"]);
+        assert_eq!(kept[0].id, "a");
+    }
+
+    #[test]
+    fn test_keywords_match_whole_words_and_moves_skip_removed() {
+        assert!(contains_keyword("i like rust", "like"));
+        assert!(!contains_keyword("this is likely fine", "like"));
+        assert!(!contains_keyword("use a ruler", "rule"));
+        assert!(contains_keyword("遵守规则", "规则"));
+
+        // 第二条被移到规范后与第一条重复，不应出现在调整分类的报告中
+        let entries = vec![
+            entry("a", "提交前必须运行 cargo fmt，禁止跳过", MemoryCategory::Rule, 2),
+            entry("b", "提交前必须运行 cargo fmt，禁止跳过", MemoryCategory::Context, 1),
+        ];
+        let (kept, report) = organize(entries, Utc::now());
+        assert_eq!(kept.len(), 1);
+        assert_eq!(report.removed.len(), 1);
+        assert!(report.moved.is_empty());
+    }
+}
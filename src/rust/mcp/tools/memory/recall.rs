@@ -0,0 +1,201 @@
+//! 记忆检索
+//!
+//! 使用本地 BM25 关键词评分对记忆排序，中日韩文本按单字和相邻双字切分，
+//! 不依赖分词词典
+
+use std::collections::{HashMap, HashSet};
+
+use super::dedup::find_similar;
+use super::{MemoryCategory, MemoryEntry};
+
+/// BM25 词频饱和参数
+const BM25_K1: f64 = 1.2;
+
+/// BM25 文档长度归一化参数
+const BM25_B: f64 = 0.75;
+
+/// 回忆条件
+#[derive(Debug, Clone, Default)]
+pub struct RecallQuery {
+    /// 检索关键词，为空时按更新时间排序
+    pub query: Option<String>,
+    /// 限定分类，为空时包含全部分类
+    pub categories: Vec<MemoryCategory>,
+    /// 输出的最大字符数
+    pub max_chars: usize,
+}
+
+/// 将文本切分为检索词
+///
+/// 拉丁字母和数字按连续片段切分并转为小写，中日韩字符输出单字和相邻双字
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut previous_cjk: Option<char> = None;
+
+    for ch in text.chars() {
+        if is_cjk(ch) {
+            flush_word(&mut word, &mut tokens);
+            tokens.push(ch.to_string());
+            if let Some(previous) = previous_cjk {
+                tokens.push(format!("{}{}", previous, ch));
+            }
+            previous_cjk = Some(ch);
+        } else if ch.is_alphanumeric() || ch == '_' {
+            previous_cjk = None;
+            word.extend(ch.to_lowercase());
+        } else {
+            previous_cjk = None;
+            flush_word(&mut word, &mut tokens);
+        }
+    }
+    flush_word(&mut word, &mut tokens);
+
+    tokens
+}
+
+fn flush_word(word: &mut String, tokens: &mut Vec<String>) {
+    if !word.is_empty() {
+        tokens.push(std::mem::take(word));
+    }
+}
+
+pub(super) fn is_cjk(ch: char) -> bool {
+    matches!(ch,
+        '\u{3040}'..='\u{30FF}'   // 平假名、片假名
+        | '\u{3400}'..='\u{4DBF}' // CJK 扩展 A
+        | '\u{4E00}'..='\u{9FFF}' // CJK 统一表意文字
+        | '\u{AC00}'..='\u{D7AF}' // 韩文音节
+        | '\u{F900}'..='\u{FAFF}' // CJK 兼容表意文字
+    )
+}
+
+/// 计算每条记忆对查询的 BM25 得分，顺序与 `entries` 一致
+pub fn bm25_scores(entries: &[MemoryEntry], query: &str) -> Vec<f64> {
+    let query_terms: HashSet<String> = tokenize(query).into_iter().collect();
+    let documents: Vec<Vec<String>> = entries.iter().map(|entry| tokenize(&entry.content)).collect();
+    if query_terms.is_empty() || documents.is_empty() {
+        return vec![0.0; entries.len()];
+    }
+
+    let total = documents.len() as f64;
+    let average_length = documents.iter().map(Vec::len).sum::<usize>() as f64 / total;
+
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for document in &documents {
+        let unique: HashSet<&str> = document.iter().map(String::as_str).collect();
+        for term in unique {
+            if query_terms.contains(term) {
+                *document_frequency.entry(term).or_default() += 1;
+            }
+        }
+    }
+
+    documents
+        .iter()
+        .map(|document| {
+            let mut term_frequency: HashMap<&str, usize> = HashMap::new();
+            for term in document {
+                *term_frequency.entry(term.as_str()).or_default() += 1;
+            }
+
+            let length_norm = 1.0 - BM25_B + BM25_B * document.len() as f64 / average_length.max(1.0);
+            document_frequency
+                .iter()
+                .map(|(term, &df)| {
+                    let tf = *term_frequency.get(term).unwrap_or(&0) as f64;
+                    let idf = ((total - df as f64 + 0.5) / (df as f64 + 0.5) + 1.0).ln();
+                    idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * length_norm)
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// 按回忆条件筛选并排序记忆
+///
+/// 规范类记忆始终排在最前；有关键词时其余分类只保留相关的条目，同类按得分排序，
+/// 没有关键词时按更新时间排序
+pub fn rank_memories(entries: Vec<MemoryEntry>, query: &RecallQuery) -> Vec<MemoryEntry> {
+    let entries: Vec<MemoryEntry> = entries
+        .into_iter()
+        .filter(|entry| query.categories.is_empty() || query.categories.contains(&entry.category))
+        .collect();
+
+    let keywords = query.query.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let scores = match keywords {
+        Some(keywords) => bm25_scores(&entries, keywords),
+        None => vec![0.0; entries.len()],
+    };
+
+    let mut ranked: Vec<(MemoryEntry, f64)> = entries
+        .into_iter()
+        .zip(scores)
+        .filter(|(entry, score)| keywords.is_none() || entry.category == MemoryCategory::Rule || *score > 0.0)
+        .collect();
+
+    ranked.sort_by(|(a, a_score), (b, b_score)| {
+        (b.category == MemoryCategory::Rule)
+            .cmp(&(a.category == MemoryCategory::Rule))
+            .then(b_score.total_cmp(a_score))
+            .then(b.updated_at.cmp(&a.updated_at))
+    });
+
+    ranked.into_iter().map(|(entry, _)| entry).collect()
+}
+
+/// 合并项目记忆和全局记忆
+///
+/// 与任一项目记忆重复或冲突的全局记忆被项目记忆覆盖，不再返回
+pub fn merge_global(project: Vec<MemoryEntry>, global: Vec<MemoryEntry>) -> Vec<MemoryEntry> {
+    let overridden: Vec<bool> = global
+        .iter()
+        .map(|entry| !find_similar(&project, &entry.content).is_empty())
+        .collect();
+
+    project
+        .into_iter()
+        .chain(global.into_iter().zip(overridden).filter(|(_, overridden)| !overridden).map(|(entry, _)| entry))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::tools::memory::types::test_entry;
+
+    fn entry(content: &str, category: MemoryCategory) -> MemoryEntry {
+        test_entry(content, content, category, 0)
+    }
+
+    #[test]
+    fn test_rank_memories_with_cjk_query() {
+        assert_eq!(tokenize("用pnpm安装"), vec!["用", "pnpm", "安", "装", "安装"]);
+
+        let entries = vec![
+            entry("数据库使用 PostgreSQL", MemoryCategory::Context),
+            entry("提交前运行 cargo fmt", MemoryCategory::Rule),
+            entry("前端依赖使用 pnpm 安装", MemoryCategory::Pattern),
+            entry("安装脚本放在 scripts 目录", MemoryCategory::Context),
+        ];
+        let query = RecallQuery {
+            query: Some("依赖安装".to_string()),
+            ..Default::default()
+        };
+
+        let ranked: Vec<String> = rank_memories(entries, &query).into_iter().map(|e| e.content).collect();
+        assert_eq!(ranked, vec!["提交前运行 cargo fmt", "前端依赖使用 pnpm 安装", "安装脚本放在 scripts 目录"]);
+    }
+
+    #[test]
+    fn test_project_memories_override_global() {
+        let project = vec![entry("提交前不要运行 cargo fmt", MemoryCategory::Rule)];
+        let global = vec![
+            entry("提交前运行 cargo fmt", MemoryCategory::Rule),
+            entry("用中文回复", MemoryCategory::Preference),
+        ];
+
+        let merged: Vec<String> = merge_global(project, global).into_iter().map(|e| e.content).collect();
+        assert_eq!(merged, vec!["提交前不要运行 cargo fmt", "用中文回复"]);
+    }
+}
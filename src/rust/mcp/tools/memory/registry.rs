@@ -0,0 +1,89 @@
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::get_standalone_config_dir;
+use crate::mcp::tools::history::{normalize_history_project, HistoryStore};
+use crate::utils::FileLock;
+
+/// 记录使用过记忆功能的项目列表，供 MCP 资源枚举使用
+const REGISTRY_FILE: &str = "memory_projects.json";
+
+fn registry_path() -> Result<PathBuf> {
+    Ok(get_standalone_config_dir()?.join(REGISTRY_FILE))
+}
+
+/// 登记一个项目路径（git 根目录）
+pub fn register_project(project_path: &str) -> Result<()> {
+    register_projects(&[project_path.to_string()])
+}
+
+/// 登记多个项目路径，已登记的会被跳过
+///
+/// 多个 MCP 进程可能同时登记，读取和写入期间持有文件锁，避免互相覆盖
+fn register_projects(project_paths: &[String]) -> Result<()> {
+    let path = registry_path()?;
+    let _lock = FileLock::exclusive(&path)?;
+    let mut projects = load_registry(&path);
+
+    let before = projects.len();
+    for project_path in project_paths {
+        if !projects.contains(project_path) {
+            projects.push(project_path.clone());
+        }
+    }
+    if projects.len() == before {
+        return Ok(());
+    }
+
+    let temp_path = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    fs::write(&temp_path, serde_json::to_string_pretty(&projects)?)?;
+    if let Err(e) = fs::rename(&temp_path, &path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// 获取所有已登记且记忆目录仍然存在的项目
+///
+/// 登记功能加入之前创建的记忆目录不在列表中，这里会补登记当前工作目录
+/// 和交互历史中出现过的、已有记忆目录的项目
+pub fn registered_projects() -> Result<Vec<PathBuf>> {
+    let path = registry_path()?;
+    let registered = load_registry(&path);
+
+    let unregistered: Vec<String> = seed_candidates()
+        .into_iter()
+        .filter(|project| !registered.contains(project) && has_memory_dir(Path::new(project)))
+        .collect();
+    if !unregistered.is_empty() {
+        register_projects(&unregistered)?;
+    }
+
+    Ok(load_registry(&path)
+        .into_iter()
+        .map(PathBuf::from)
+        .filter(|p| has_memory_dir(p))
+        .collect())
+}
+
+/// 可能已有记忆目录的项目：当前工作目录和交互历史中的项目
+fn seed_candidates() -> Vec<String> {
+    let current = std::env::current_dir()
+        .ok()
+        .and_then(|dir| normalize_history_project(&dir.to_string_lossy()));
+    let history = HistoryStore::open().and_then(|store| store.projects()).unwrap_or_default();
+    current.into_iter().chain(history).collect()
+}
+
+fn has_memory_dir(project_path: &Path) -> bool {
+    project_path.join(".cunzhi-memory").is_dir()
+}
+
+fn load_registry(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
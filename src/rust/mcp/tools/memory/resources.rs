@@ -0,0 +1,183 @@
+use anyhow::Result;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use ring::digest::{digest, SHA256};
+use rmcp::{ErrorData as McpError, model::*};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::registry::registered_projects;
+use super::MemoryCategory;
+
+/// 记忆资源 URI 的协议名
+pub const MEMORY_RESOURCE_SCHEME: &str = "cunzhi-memory";
+
+/// 元数据资源名
+const METADATA_RESOURCE: &str = "metadata";
+
+/// URI 中项目名需要编码的字符
+const PROJECT_KEY_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'/')
+    .add(b'%')
+    .add(b'?')
+    .add(b'#')
+    .add(b':');
+
+/// 项目记忆资源
+///
+/// 将每个已登记项目的 `.cunzhi-memory` 分类文件和 metadata.json 发布为 MCP 资源，
+/// URI 形如 `cunzhi-memory://<project>/rules`
+pub struct MemoryResources;
+
+impl MemoryResources {
+    /// 列出所有项目的记忆资源
+    pub fn list_resources() -> Result<Vec<Resource>> {
+        let mut resources = Vec::new();
+
+        for (key, project_path) in project_keys()? {
+            let memory_dir = project_path.join(".cunzhi-memory");
+            let project_display = project_path.to_string_lossy();
+
+            for category in MemoryCategory::ALL.iter() {
+                let file_path = memory_dir.join(category.file_name());
+                if !file_path.exists() {
+                    continue;
+                }
+
+                let mut resource = RawResource::new(
+                    build_uri(&key, category.resource_name()),
+                    format!("{}/{}", key, category.resource_name()),
+                );
+                resource.title = Some(format!("{} - {}", key, category.title()));
+                resource.description = Some(format!("项目 {} 的{}", project_display, category.title()));
+                resource.mime_type = Some("text/markdown".to_string());
+                resource.size = file_size(&file_path);
+                resources.push(resource.no_annotation());
+            }
+
+            let metadata_path = memory_dir.join("metadata.json");
+            if metadata_path.exists() {
+                let mut resource = RawResource::new(
+                    build_uri(&key, METADATA_RESOURCE),
+                    format!("{}/{}", key, METADATA_RESOURCE),
+                );
+                resource.title = Some(format!("{} - 记忆元数据", key));
+                resource.description = Some(format!("项目 {} 的记忆元数据", project_display));
+                resource.mime_type = Some("application/json".to_string());
+                resource.size = file_size(&metadata_path);
+                resources.push(resource.no_annotation());
+            }
+        }
+
+        Ok(resources)
+    }
+
+    /// 读取指定 URI 的记忆资源
+    pub fn read_resource(uri: &str) -> Result<ResourceContents, McpError> {
+        let (key, name) = parse_uri(uri)
+            .ok_or_else(|| McpError::invalid_params(format!("无效的记忆资源URI: {}", uri), None))?;
+
+        let projects = project_keys()
+            .map_err(|e| McpError::internal_error(format!("读取记忆项目列表失败: {}", e), None))?;
+        let project_path = projects.get(&key)
+            .ok_or_else(|| McpError::resource_not_found(format!("未找到记忆项目: {}", key), None))?;
+
+        let memory_dir = project_path.join(".cunzhi-memory");
+        let (file_path, mime_type) = if name == METADATA_RESOURCE {
+            (memory_dir.join("metadata.json"), "application/json")
+        } else {
+            let category = MemoryCategory::ALL.iter()
+                .find(|c| c.resource_name() == name)
+                .ok_or_else(|| McpError::resource_not_found(format!("未知的记忆资源: {}", name), None))?;
+            (memory_dir.join(category.file_name()), "text/markdown")
+        };
+
+        let text = fs::read_to_string(&file_path)
+            .map_err(|e| McpError::resource_not_found(
+                format!("读取记忆文件失败: {} ({})", file_path.display(), e),
+                None,
+            ))?;
+
+        Ok(ResourceContents::TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some(mime_type.to_string()),
+            text,
+            meta: None,
+        })
+    }
+}
+
+/// 为已登记项目生成 URI 中使用的项目名
+///
+/// 默认使用目录名，目录名重复时追加路径哈希以区分
+fn project_keys() -> Result<HashMap<String, PathBuf>> {
+    let projects = registered_projects()?;
+
+    let mut name_counts: HashMap<String, usize> = HashMap::new();
+    for path in &projects {
+        *name_counts.entry(dir_name(path)).or_default() += 1;
+    }
+
+    let mut keys = HashMap::new();
+    for path in projects {
+        let name = dir_name(&path);
+        let key = if name_counts.get(&name).copied().unwrap_or(0) > 1 {
+            let hash = digest(&SHA256, path.to_string_lossy().as_bytes());
+            format!("{}-{}", name, &hex::encode(hash.as_ref())[..8])
+        } else {
+            name
+        };
+        keys.insert(key, path);
+    }
+
+    Ok(keys)
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "project".to_string())
+}
+
+fn build_uri(project_key: &str, name: &str) -> String {
+    format!(
+        "{}://{}/{}",
+        MEMORY_RESOURCE_SCHEME,
+        utf8_percent_encode(project_key, PROJECT_KEY_ENCODE_SET),
+        name
+    )
+}
+
+fn parse_uri(uri: &str) -> Option<(String, String)> {
+    let rest = uri.strip_prefix(MEMORY_RESOURCE_SCHEME)?.strip_prefix("://")?;
+    let (project, name) = rest.split_once('/')?;
+    let project = percent_decode_str(project).decode_utf8().ok()?.to_string();
+    if project.is_empty() || name.is_empty() {
+        return None;
+    }
+    Some((project, name.to_string()))
+}
+
+fn file_size(path: &Path) -> Option<u32> {
+    fs::metadata(path).ok().map(|m| m.len() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uri_round_trip() {
+        let uri = build_uri("my project", "rules");
+        assert_eq!(uri, "cunzhi-memory://my%20project/rules");
+        assert_eq!(parse_uri(&uri), Some(("my project".to_string(), "rules".to_string())));
+    }
+
+    #[test]
+    fn test_parse_invalid_uri() {
+        assert_eq!(parse_uri("file:///tmp/rules.md"), None);
+        assert_eq!(parse_uri("cunzhi-memory://foo"), None);
+        assert_eq!(parse_uri("cunzhi-memory:///rules"), None);
+    }
+}
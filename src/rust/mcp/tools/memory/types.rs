@@ -9,6 +9,69 @@ pub struct MemoryEntry {
     pub category: MemoryCategory,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub scope: MemoryScope,
+}
+
+impl MemoryEntry {
+    /// 展示用的短ID，更新和删除时可以代替完整ID
+    pub fn short_id(&self) -> &str {
+        self.id.get(..8).unwrap_or(&self.id)
+    }
+}
+
+/// 测试用的记忆条目，创建和更新时间为 `age_days` 天前
+#[cfg(test)]
+pub(crate) fn test_entry(id: &str, content: &str, category: MemoryCategory, age_days: i64) -> MemoryEntry {
+    let at = Utc::now() - chrono::Duration::days(age_days);
+    MemoryEntry {
+        id: id.to_string(),
+        content: content.to_string(),
+        category,
+        created_at: at,
+        updated_at: at,
+        scope: Default::default(),
+    }
+}
+
+/// 添加记忆的结果
+#[derive(Debug, Clone)]
+pub enum AddOutcome {
+    /// 已添加；`conflicts` 为可能与之冲突的已有记忆
+    Added { entry: MemoryEntry, conflicts: Vec<MemoryEntry> },
+    /// 新内容是已有记忆的补充，已合并到该记忆
+    Merged { entry: MemoryEntry, previous: String },
+    /// 与已有记忆重复，未添加
+    Duplicate { existing: MemoryEntry },
+}
+
+/// 记忆范围
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryScope {
+    /// 存放在项目 git 根目录下，只对该项目生效
+    #[default]
+    Project,
+    /// 存放在寸止配置目录下，对所有项目生效
+    Global,
+}
+
+impl MemoryScope {
+    /// 按请求中的名称解析范围
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            "project" => Some(MemoryScope::Project),
+            "global" => Some(MemoryScope::Global),
+            _ => None,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            MemoryScope::Project => "项目",
+            MemoryScope::Global => "全局",
+        }
+    }
 }
 
 /// 记忆分类
@@ -20,11 +83,74 @@ pub enum MemoryCategory {
     Context,     // 项目上下文信息
 }
 
+impl MemoryCategory {
+    /// 全部分类，按展示顺序排列
+    pub const ALL: [MemoryCategory; 4] = [
+        MemoryCategory::Rule,
+        MemoryCategory::Preference,
+        MemoryCategory::Pattern,
+        MemoryCategory::Context,
+    ];
+
+    /// 分类对应的记忆文件名
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            MemoryCategory::Rule => "rules.md",
+            MemoryCategory::Preference => "preferences.md",
+            MemoryCategory::Pattern => "patterns.md",
+            MemoryCategory::Context => "context.md",
+        }
+    }
+
+    /// 按请求中的名称解析分类
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            "rule" => Some(MemoryCategory::Rule),
+            "preference" => Some(MemoryCategory::Preference),
+            "pattern" => Some(MemoryCategory::Pattern),
+            "context" => Some(MemoryCategory::Context),
+            _ => None,
+        }
+    }
+
+    /// 分类简称，用于回忆输出
+    pub fn short_title(&self) -> &'static str {
+        match self {
+            MemoryCategory::Rule => "规范",
+            MemoryCategory::Preference => "偏好",
+            MemoryCategory::Pattern => "模式",
+            MemoryCategory::Context => "背景",
+        }
+    }
+
+    /// 分类标题
+    pub fn title(&self) -> &'static str {
+        match self {
+            MemoryCategory::Rule => "开发规范和规则",
+            MemoryCategory::Preference => "用户偏好设置",
+            MemoryCategory::Pattern => "常用模式和最佳实践",
+            MemoryCategory::Context => "项目上下文信息",
+        }
+    }
+
+    /// 分类在资源 URI 中的名称
+    pub fn resource_name(&self) -> &'static str {
+        match self {
+            MemoryCategory::Rule => "rules",
+            MemoryCategory::Preference => "preferences",
+            MemoryCategory::Pattern => "patterns",
+            MemoryCategory::Context => "context",
+        }
+    }
+}
+
 /// 记忆元数据
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MemoryMetadata {
     pub project_path: String,
-    pub last_organized: DateTime<Utc>,
+    /// 上次执行整理操作的时间，从未整理时为空
+    #[serde(default)]
+    pub last_organized: Option<DateTime<Utc>>,
     pub total_entries: usize,
     pub version: String,
 }
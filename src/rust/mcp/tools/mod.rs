@@ -5,9 +5,14 @@ pub mod memory;
 pub mod interaction;
 pub mod enhance;
 pub mod acemcp;
+pub mod review;
+pub mod notify;
+pub mod history;
 
 // 重新导出工具以便访问
 pub use memory::MemoryTool;
-pub use interaction::InteractionTool;
+pub use interaction::{InteractionContext, InteractionTool};
 pub use enhance::EnhanceTool;
 pub use acemcp::AcemcpTool;
+pub use review::ReviewTool;
+pub use notify::NotifyTool;
@@ -0,0 +1,155 @@
+use rmcp::{ErrorData as McpError, model::*};
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::config::{config_watcher, TelegramConfig};
+use crate::constants::mcp;
+use crate::mcp::handlers::create_tauri_notification;
+use crate::mcp::NotifyRequest;
+use crate::telegram::{process_telegram_markdown, telegram_core_from_config};
+use crate::tui::has_graphical_display;
+use crate::{log_debug, log_important};
+
+/// 渠道发送状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Sent,
+    /// 已交给后台发送，结果只记录在日志中
+    Queued,
+    Skipped,
+    Failed,
+}
+
+/// 单个渠道的发送结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelDelivery {
+    pub channel: &'static str,
+    pub status: DeliveryStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ChannelDelivery {
+    fn new(channel: &'static str, status: DeliveryStatus, detail: impl Into<Option<String>>) -> Self {
+        Self { channel, status, detail: detail.into() }
+    }
+}
+
+/// 进度通知工具
+///
+/// 与寸止使用相同的渠道，但只发送不等待：桌面通知窗口显示几秒后自动关闭
+#[derive(Clone)]
+pub struct NotifyTool;
+
+impl NotifyTool {
+    pub async fn notify(request: NotifyRequest) -> Result<CallToolResult, McpError> {
+        if request.message.trim().is_empty() {
+            return Err(McpError::invalid_params("通知内容不能为空".to_string(), None));
+        }
+
+        let config = config_watcher().current();
+        let mut deliveries = Vec::new();
+
+        // 桌面通知，提示音由通知窗口播放
+        let telegram_only = config.telegram_config.enabled && config.telegram_config.hide_frontend_popup;
+        let desktop = if telegram_only {
+            ChannelDelivery::new("桌面通知", DeliveryStatus::Skipped, "纯 Telegram 模式".to_string())
+        } else if !has_graphical_display() {
+            ChannelDelivery::new("桌面通知", DeliveryStatus::Skipped, "当前环境没有图形界面".to_string())
+        } else {
+            match create_tauri_notification(&request) {
+                Ok(()) => ChannelDelivery::new("桌面通知", DeliveryStatus::Sent, None),
+                Err(e) => ChannelDelivery::new("桌面通知", DeliveryStatus::Failed, e.to_string()),
+            }
+        };
+
+        let sound = if !config.audio_config.notification_enabled {
+            ChannelDelivery::new("提示音", DeliveryStatus::Skipped, "提示音未启用".to_string())
+        } else if desktop.status == DeliveryStatus::Sent {
+            ChannelDelivery::new("提示音", DeliveryStatus::Queued, "随桌面通知播放".to_string())
+        } else {
+            ChannelDelivery::new("提示音", DeliveryStatus::Skipped, "桌面通知未发送".to_string())
+        };
+        deliveries.push(desktop);
+        deliveries.push(sound);
+        deliveries.push(Self::queue_telegram(&request, &config.telegram_config));
+
+        for delivery in &deliveries {
+            if delivery.status == DeliveryStatus::Failed {
+                log_important!(warn, "通知发送到 {} 失败: {:?}", delivery.channel, delivery.detail);
+            }
+        }
+        log_debug!("通知发送结果: {:?}", deliveries);
+
+        Ok(build_notify_result(&deliveries))
+    }
+
+    /// 在后台发送 Telegram 通知，工具立即返回
+    fn queue_telegram(request: &NotifyRequest, telegram_config: &TelegramConfig) -> ChannelDelivery {
+        let core = match telegram_core_from_config(telegram_config) {
+            Ok(Some(core)) => core,
+            Ok(None) => return ChannelDelivery::new("Telegram", DeliveryStatus::Skipped, "未启用".to_string()),
+            Err(e) => return ChannelDelivery::new("Telegram", DeliveryStatus::Failed, e.to_string()),
+        };
+
+        let message = format_telegram_message(request);
+        let is_markdown = request.is_markdown;
+        tokio::spawn(async move {
+            let send = core.send_message_with_markdown(&message, is_markdown);
+            match tokio::time::timeout(Duration::from_secs(mcp::NOTIFY_TELEGRAM_TIMEOUT_SECS), send).await {
+                Ok(Ok(())) => log_debug!("Telegram 通知已发送"),
+                Ok(Err(e)) => log_important!(warn, "Telegram 通知发送失败: {}", e),
+                Err(_) => log_important!(warn, "Telegram 通知发送超时"),
+            }
+        });
+
+        ChannelDelivery::new("Telegram", DeliveryStatus::Queued, "后台发送中".to_string())
+    }
+}
+
+/// Telegram 通知文本：级别图标、标题和内容
+fn format_telegram_message(request: &NotifyRequest) -> String {
+    let header = match &request.title {
+        Some(title) if request.is_markdown => format!("{} **{}**\n\n", request.level.icon(), title),
+        Some(title) => format!("{} {}\n\n", request.level.icon(), title),
+        None => format!("{} ", request.level.icon()),
+    };
+    let message = format!("{}{}", header, request.message);
+
+    if request.is_markdown {
+        process_telegram_markdown(&message)
+    } else {
+        message
+    }
+}
+
+fn build_notify_result(deliveries: &[ChannelDelivery]) -> CallToolResult {
+    let delivered = deliveries
+        .iter()
+        .any(|d| matches!(d.status, DeliveryStatus::Sent | DeliveryStatus::Queued));
+    let mut lines = vec![if delivered {
+        "通知已发出:".to_string()
+    } else {
+        "通知未送达任何渠道:".to_string()
+    }];
+    for delivery in deliveries {
+        let status = match delivery.status {
+            DeliveryStatus::Sent => "已发送",
+            DeliveryStatus::Queued => "已排队",
+            DeliveryStatus::Skipped => "跳过",
+            DeliveryStatus::Failed => "失败",
+        };
+        lines.push(match &delivery.detail {
+            Some(detail) => format!("- {}: {}（{}）", delivery.channel, status, detail),
+            None => format!("- {}: {}", delivery.channel, status),
+        });
+    }
+
+    let mut result = CallToolResult::success(vec![Content::text(lines.join("\n"))]);
+    result.structured_content = Some(serde_json::json!({
+        "delivered": delivered,
+        "channels": deliveries,
+    }));
+    result
+}
@@ -0,0 +1,8 @@
+//! 进度通知工具模块
+//!
+//! 向桌面、Telegram 发送通知并播放提示音，立即返回各渠道的发送状态，不等待用户回复
+
+pub mod mcp;
+
+// 重新导出主要类型和功能
+pub use mcp::{ChannelDelivery, DeliveryStatus, NotifyTool};
@@ -0,0 +1,355 @@
+use anyhow::Result;
+use std::path::Path;
+
+use super::types::{DiffFile, DiffHunk, FileStatus};
+
+/// 解析 unified diff（`git diff` 或 `diff -u` 的输出）
+///
+/// 无法识别的行（`index`、`mode` 等元信息）会被忽略
+pub fn parse_unified_diff(diff: &str) -> Vec<DiffFile> {
+    let mut files: Vec<DiffFile> = Vec::new();
+    // 当前 hunk 剩余的旧/新行数，用于区分 hunk 内容和下一个文件头
+    let mut remaining = (0u32, 0u32);
+
+    for line in diff.lines() {
+        if remaining != (0, 0) {
+            if let Some(file) = files.last_mut() {
+                if let Some(hunk) = file.hunks.last_mut() {
+                    match line.chars().next() {
+                        Some('+') => remaining.1 = remaining.1.saturating_sub(1),
+                        Some('-') => remaining.0 = remaining.0.saturating_sub(1),
+                        Some('\\') => {}
+                        // 部分工具会去掉空上下文行的前导空格
+                        Some(' ') | None => {
+                            remaining.0 = remaining.0.saturating_sub(1);
+                            remaining.1 = remaining.1.saturating_sub(1);
+                        }
+                        Some(_) => {
+                            remaining = (0, 0);
+                            parse_header_line(&mut files, line, &mut remaining);
+                            continue;
+                        }
+                    }
+                    hunk.lines.push(line.to_string());
+                    continue;
+                }
+            }
+        }
+
+        // hunk 结束后紧跟的 "\ No newline at end of file"
+        if line.starts_with('\\') {
+            if let Some(hunk) = files.last_mut().and_then(|file| file.hunks.last_mut()) {
+                hunk.lines.push(line.to_string());
+            }
+            continue;
+        }
+
+        parse_header_line(&mut files, line, &mut remaining);
+    }
+
+    // 补齐 hunk 编号
+    for (file_index, file) in files.iter_mut().enumerate() {
+        for (hunk_index, hunk) in file.hunks.iter_mut().enumerate() {
+            hunk.id = format!("{}.{}", file_index + 1, hunk_index + 1);
+        }
+    }
+
+    files
+}
+
+fn parse_header_line(files: &mut Vec<DiffFile>, line: &str, remaining: &mut (u32, u32)) {
+    if let Some(rest) = line.strip_prefix("diff --git ") {
+        let (old_path, new_path) = split_git_paths(rest);
+        files.push(DiffFile {
+            path: new_path,
+            old_path: Some(old_path),
+            status: FileStatus::Modified,
+            binary: false,
+            hunks: Vec::new(),
+        });
+    } else if let Some(path) = line.strip_prefix("--- ") {
+        // 非 git 格式的 diff 没有 `diff --git` 行，以 `---` 开始新文件
+        let needs_new_file = files
+            .last()
+            .map(|file| !file.hunks.is_empty() || file.binary)
+            .unwrap_or(true);
+        if needs_new_file {
+            files.push(DiffFile {
+                path: String::new(),
+                old_path: None,
+                status: FileStatus::Modified,
+                binary: false,
+                hunks: Vec::new(),
+            });
+        }
+        if let Some(file) = files.last_mut() {
+            match clean_path(path) {
+                Some(path) => {
+                    if file.path.is_empty() {
+                        file.path = path.clone();
+                    }
+                    file.old_path = Some(path);
+                }
+                None => {
+                    file.old_path = None;
+                    file.status = FileStatus::Added;
+                }
+            }
+        }
+    } else if let Some(path) = line.strip_prefix("+++ ") {
+        if let Some(file) = files.last_mut() {
+            match clean_path(path) {
+                Some(path) => file.path = path,
+                None => file.status = FileStatus::Deleted,
+            }
+        }
+    } else if line.starts_with("new file mode") {
+        if let Some(file) = files.last_mut() {
+            file.status = FileStatus::Added;
+            file.old_path = None;
+        }
+    } else if line.starts_with("deleted file mode") {
+        if let Some(file) = files.last_mut() {
+            file.status = FileStatus::Deleted;
+        }
+    } else if let Some(path) = line.strip_prefix("rename to ") {
+        if let Some(file) = files.last_mut() {
+            file.status = FileStatus::Renamed;
+            file.path = path.to_string();
+        }
+    } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+        if let Some(file) = files.last_mut() {
+            file.binary = true;
+        }
+    } else if line.starts_with("@@") {
+        if let (Some(file), Some(hunk)) = (files.last_mut(), parse_hunk_header(line)) {
+            *remaining = (hunk.old_lines, hunk.new_lines);
+            file.hunks.push(hunk);
+        }
+    }
+
+    // 重命名但内容不变时路径相同，保持为修改
+    if let Some(file) = files.last_mut() {
+        if file.status == FileStatus::Renamed && file.old_path.as_deref() == Some(file.path.as_str()) {
+            file.status = FileStatus::Modified;
+        }
+    }
+}
+
+/// 解析 `@@ -a,b +c,d @@ section` 头部
+fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let body = line.strip_prefix("@@ ")?;
+    let end = body.find(" @@")?;
+    let mut ranges = body[..end].split_whitespace();
+    let (old_start, old_lines) = parse_range(ranges.next()?.strip_prefix('-')?)?;
+    let (new_start, new_lines) = parse_range(ranges.next()?.strip_prefix('+')?)?;
+
+    Some(DiffHunk {
+        id: String::new(),
+        header: line.to_string(),
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        lines: Vec::new(),
+    })
+}
+
+fn parse_range(range: &str) -> Option<(u32, u32)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// 拆分 `a/old b/new`
+fn split_git_paths(rest: &str) -> (String, String) {
+    match rest.rfind(" b/") {
+        Some(index) => (
+            rest[..index].trim_start_matches("a/").to_string(),
+            rest[index + 3..].to_string(),
+        ),
+        None => (rest.to_string(), rest.to_string()),
+    }
+}
+
+/// 去掉 `a/`、`b/` 前缀和时间戳，`/dev/null` 返回 `None`
+fn clean_path(path: &str) -> Option<String> {
+    let path = path.split('\t').next().unwrap_or(path).trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+/// 将选中的 hunk 重新组合为可以 `git apply` 的补丁
+pub fn render_patch(files: &[DiffFile], include: impl Fn(&DiffHunk) -> bool) -> String {
+    let mut patch = String::new();
+
+    for file in files {
+        let hunks: Vec<&DiffHunk> = file.hunks.iter().filter(|hunk| include(hunk)).collect();
+        if hunks.is_empty() {
+            continue;
+        }
+
+        let old_path = file.old_path.as_deref().unwrap_or(&file.path);
+        patch.push_str(&format!("diff --git a/{} b/{}\n", old_path, file.path));
+        match file.status {
+            FileStatus::Added => {
+                patch.push_str("--- /dev/null\n");
+                patch.push_str(&format!("+++ b/{}\n", file.path));
+            }
+            FileStatus::Deleted => {
+                patch.push_str(&format!("--- a/{}\n", old_path));
+                patch.push_str("+++ /dev/null\n");
+            }
+            FileStatus::Modified | FileStatus::Renamed => {
+                patch.push_str(&format!("--- a/{}\n", old_path));
+                patch.push_str(&format!("+++ b/{}\n", file.path));
+            }
+        }
+
+        for hunk in hunks {
+            patch.push_str(&hunk.header);
+            patch.push('\n');
+            for line in &hunk.lines {
+                patch.push_str(line);
+                patch.push('\n');
+            }
+        }
+    }
+
+    patch
+}
+
+/// git 的空树对象，仓库还没有提交时作为比较基准
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// 读取项目中某个修订范围的 diff
+///
+/// `revision_range` 为空时返回已跟踪文件相对 HEAD 的未提交改动（仓库还没有提交时相对空树），
+/// 未跟踪的新文件不包含在内，见 [`list_untracked_files`]
+pub async fn load_git_diff(project_path: &Path, revision_range: Option<&str>) -> Result<String> {
+    let mut command = tokio::process::Command::new("git");
+    command
+        .arg("-C")
+        .arg(project_path)
+        .args(["diff", "--no-color", "--no-ext-diff"]);
+
+    match revision_range.map(str::trim).filter(|range| !range.is_empty()) {
+        // 防止把修订范围当作命令行选项
+        Some(range) if range.starts_with('-') => anyhow::bail!("无效的修订范围: {}", range),
+        Some(range) => {
+            command.arg(range);
+        }
+        None => {
+            let has_head = run_git(project_path, &["rev-parse", "--verify", "--quiet", "HEAD"]).await.is_ok();
+            command.arg(if has_head { "HEAD" } else { EMPTY_TREE });
+        }
+    }
+
+    let output = command
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("执行 git diff 失败: {}", e))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git diff 执行失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// 列出未跟踪且未被忽略的文件，这些文件不会出现在未提交改动的 diff 中
+pub async fn list_untracked_files(project_path: &Path) -> Result<Vec<String>> {
+    let output = run_git(project_path, &["ls-files", "--others", "--exclude-standard"]).await?;
+    Ok(output.lines().map(str::to_string).filter(|line| !line.is_empty()).collect())
+}
+
+async fn run_git(project_path: &Path, args: &[&str]) -> Result<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(project_path)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("执行 git {} 失败: {}", args[0], e))?;
+
+    if !output.status.success() {
+        anyhow::bail!("git {} 执行失败: {}", args[0], String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIT_DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    old();
++    new();
+ }
+@@ -10,2 +10,3 @@ fn other() {
+ let a = 1;
++let b = 2;
+ let c = 3;
+diff --git a/docs/new.md b/docs/new.md
+new file mode 100644
+--- /dev/null
++++ b/docs/new.md
+@@ -0,0 +1 @@
++--- not a header
+\\ No newline at end of file
+";
+
+    #[test]
+    fn test_parse_git_diff() {
+        let files = parse_unified_diff(GIT_DIFF);
+        assert_eq!(files.len(), 2);
+
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].status, FileStatus::Modified);
+        assert_eq!(files[0].hunks.len(), 2);
+        assert_eq!(files[0].hunks[1].id, "1.2");
+        assert_eq!(files[0].hunks[1].lines, vec![" let a = 1;", "+let b = 2;", " let c = 3;"]);
+
+        assert_eq!(files[1].path, "docs/new.md");
+        assert_eq!(files[1].status, FileStatus::Added);
+        assert_eq!(files[1].old_path, None);
+        assert_eq!(files[1].hunks[0].lines, vec!["+--- not a header", "\\ No newline at end of file"]);
+    }
+
+    #[test]
+    fn test_render_patch_keeps_selected_hunks() {
+        let files = parse_unified_diff(GIT_DIFF);
+        let patch = render_patch(&files, |hunk| hunk.id == "1.2");
+
+        assert!(patch.starts_with("diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n"));
+        assert!(patch.contains("@@ -10,2 +10,3 @@"));
+        assert!(!patch.contains("new();"));
+        assert!(!patch.contains("docs/new.md"));
+    }
+
+    #[test]
+    fn test_parse_plain_unified_diff() {
+        let diff = "--- a.txt\t2024-01-01\n+++ b.txt\t2024-01-02\n@@ -1 +1 @@\n-a\n+b\n";
+        let files = parse_unified_diff(diff);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "b.txt");
+        assert_eq!(files[0].old_path.as_deref(), Some("a.txt"));
+        assert_eq!(files[0].hunks[0].id, "1.1");
+    }
+}
@@ -0,0 +1,283 @@
+use anyhow::Result;
+use rmcp::{ErrorData as McpError, model::*};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::diff::{list_untracked_files, load_git_diff, parse_unified_diff, render_patch};
+use super::types::{DiffFile, DiffReview, ReviewVerdict};
+use crate::config::config_watcher;
+use crate::mcp::channels::{ask_first, InteractionChannel, PopupChannel};
+use crate::mcp::utils::{
+    decode_and_normalize_path, generate_request_id, popup_error, project_path_error, response_error,
+    validate_project_path,
+};
+use crate::mcp::{PopupRequest, ShenRequest};
+use crate::tui::should_use_tui;
+use crate::log_debug;
+
+/// 弹窗返回的审查响应
+#[derive(Debug, Deserialize)]
+struct ReviewResponse {
+    #[serde(default)]
+    review: Option<ReviewVerdict>,
+}
+
+/// 代码变更审查工具
+///
+/// 在桌面弹窗中逐块审查 diff，返回接受的 hunk 和审查评论
+#[derive(Clone)]
+pub struct ReviewTool;
+
+impl ReviewTool {
+    pub async fn shen(request: ShenRequest) -> Result<CallToolResult, McpError> {
+        if let Err(e) = validate_project_path(&request.project_path) {
+            return Err(project_path_error(format!(
+                "路径验证失败: {}\n原始路径: {}",
+                e, request.project_path
+            ))
+            .into());
+        }
+        let project_path = decode_and_normalize_path(&request.project_path)
+            .map_err(|e| project_path_error(e.to_string()))?;
+
+        let mut untracked = Vec::new();
+        let (diff, source) = match (&request.diff, &request.revision_range) {
+            (Some(_), Some(_)) => {
+                return Err(McpError::invalid_params("diff 和 revision_range 只能提供一个".to_string(), None));
+            }
+            (Some(diff), None) => (diff.clone(), "unified diff".to_string()),
+            (None, range) => {
+                let diff = load_git_diff(Path::new(&project_path), range.as_deref())
+                    .await
+                    .map_err(|e| McpError::internal_error(format!("读取 git diff 失败: {}", e), None))?;
+                if range.as_deref().map(str::trim).is_none_or(str::is_empty) {
+                    // 未跟踪的文件不在 diff 中，只在结果中列出
+                    untracked = list_untracked_files(Path::new(&project_path)).await.unwrap_or_default();
+                }
+                let source = range.clone().unwrap_or_else(|| "未提交的改动".to_string());
+                (diff, source)
+            }
+        };
+
+        let files = parse_unified_diff(&diff);
+        if files.is_empty() {
+            let mut text = format!("没有需要审查的改动（{}）", source);
+            if !untracked.is_empty() {
+                text.push_str(&format!("\n{}", describe_untracked(&untracked)));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(text)]));
+        }
+
+        let channels = Self::review_channels().map_err(|e| popup_error(e.to_string()))?;
+        let popup_request = PopupRequest {
+            id: generate_request_id(),
+            message: request
+                .message
+                .unwrap_or_else(|| format!("请审查以下改动（{}）", source)),
+            predefined_options: None,
+            selection_mode: Default::default(),
+            is_markdown: true,
+            timeout_seconds: None,
+            default_option: None,
+            fields: None,
+            client: None,
+            project_path: Some(project_path.clone()),
+            questions: None,
+            review: Some(DiffReview {
+                project_path,
+                source,
+                files,
+            }),
+        };
+
+        let response = ask_first(&channels, &popup_request)
+            .await
+            .map_err(|e| popup_error(e.to_string()))?;
+
+        if response.trim() == "CANCELLED" {
+            return Ok(CallToolResult::success(vec![Content::text("用户取消了审查".to_string())]));
+        }
+
+        let verdict = serde_json::from_str::<ReviewResponse>(&response)
+            .ok()
+            .and_then(|response| response.review)
+            .ok_or_else(|| response_error("弹窗未返回审查结果"))?;
+
+        let files = popup_request.review.map(|review| review.files).unwrap_or_default();
+        Ok(build_review_result(&files, &verdict, &untracked))
+    }
+
+    /// 审查需要逐块展示 diff，目前只有桌面弹窗支持
+    fn review_channels() -> Result<Vec<Box<dyn InteractionChannel>>> {
+        let config = config_watcher().current();
+        if config.telegram_config.enabled && config.telegram_config.hide_frontend_popup {
+            anyhow::bail!("纯 Telegram 模式下无法打开代码审查弹窗");
+        }
+        if should_use_tui() {
+            anyhow::bail!("当前环境没有图形界面，无法打开代码审查弹窗");
+        }
+        Ok(vec![Box::new(PopupChannel)])
+    }
+}
+
+fn describe_untracked(untracked: &[String]) -> String {
+    format!("⚠️ 以下未跟踪的文件不在审查范围内: {}", untracked.join(", "))
+}
+
+/// 根据审查结果生成工具返回值
+///
+/// 未出现在审查结果中的 hunk 和没有 hunk 的文件视为拒绝；没有 hunk 的文件不包含在 `accepted_patch` 中
+fn build_review_result(files: &[DiffFile], verdict: &ReviewVerdict, untracked: &[String]) -> CallToolResult {
+    let decisions: HashMap<&str, _> = verdict
+        .hunks
+        .iter()
+        .map(|hunk| (hunk.id.as_str(), hunk))
+        .collect();
+    let file_decisions: HashMap<&str, bool> = verdict
+        .whole_files
+        .iter()
+        .map(|file| (file.path.as_str(), file.accepted))
+        .collect();
+
+    let mut accepted = Vec::new();
+    let mut rejected = Vec::new();
+    let mut accepted_files = Vec::new();
+    let mut rejected_files = Vec::new();
+    let mut comments = Vec::new();
+    let mut lines = vec!["审查结果:".to_string()];
+
+    for file in files {
+        lines.push(format!("📄 {}", file.path));
+        if file.hunks.is_empty() {
+            // 二进制、纯重命名或仅权限变更的文件只能整体接受或拒绝
+            let is_accepted = file_decisions.get(file.path.as_str()).copied().unwrap_or(false);
+            let entry = serde_json::json!({
+                "file": file.path,
+                "old_path": file.old_path,
+                "status": file.status,
+                "binary": file.binary,
+            });
+            if is_accepted {
+                accepted_files.push(entry);
+            } else {
+                rejected_files.push(entry);
+            }
+            lines.push(format!(
+                "  {} 整个文件（无文本改动，不包含在补丁中）",
+                if is_accepted { "✅" } else { "❌" }
+            ));
+            continue;
+        }
+        for hunk in &file.hunks {
+            let decision = decisions.get(hunk.id.as_str());
+            let is_accepted = decision.map(|d| d.accepted).unwrap_or(false);
+            let entry = serde_json::json!({
+                "id": hunk.id,
+                "file": file.path,
+                "header": hunk.header,
+            });
+            if is_accepted {
+                accepted.push(entry);
+            } else {
+                rejected.push(entry);
+            }
+
+            let comment = decision.and_then(|d| d.comment.as_deref()).map(str::trim).filter(|c| !c.is_empty());
+            lines.push(format!(
+                "  {} [{}] {}{}",
+                if is_accepted { "✅" } else { "❌" },
+                hunk.id,
+                hunk.header,
+                comment.map(|c| format!("\n     💬 {}", c)).unwrap_or_default()
+            ));
+            if let Some(comment) = comment {
+                comments.push(serde_json::json!({
+                    "file": file.path,
+                    "hunk": hunk.id,
+                    "comment": comment,
+                }));
+            }
+        }
+    }
+
+    for file_comment in &verdict.files {
+        let comment = file_comment.comment.trim();
+        if comment.is_empty() {
+            continue;
+        }
+        lines.push(format!("💬 {}: {}", file_comment.path, comment));
+        comments.push(serde_json::json!({
+            "file": file_comment.path,
+            "comment": comment,
+        }));
+    }
+
+    let summary = verdict.comment.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if let Some(summary) = summary {
+        lines.push(format!("📝 整体评论: {}", summary));
+    }
+
+    let approved = rejected.is_empty() && rejected_files.is_empty();
+    lines.push(format!(
+        "共 {} 个代码块，接受 {} 个，拒绝 {} 个",
+        accepted.len() + rejected.len(),
+        accepted.len(),
+        rejected.len()
+    ));
+    if !accepted_files.is_empty() || !rejected_files.is_empty() {
+        lines.push(format!(
+            "无文本改动的文件 {} 个，接受 {} 个，拒绝 {} 个",
+            accepted_files.len() + rejected_files.len(),
+            accepted_files.len(),
+            rejected_files.len()
+        ));
+    }
+    if !untracked.is_empty() {
+        lines.push(describe_untracked(untracked));
+    }
+    log_debug!("审查完成: 接受 {} 个，拒绝 {} 个", accepted.len(), rejected.len());
+
+    let accepted_patch = render_patch(files, |hunk| {
+        decisions.get(hunk.id.as_str()).map(|d| d.accepted).unwrap_or(false)
+    });
+
+    let mut result = CallToolResult::success(vec![Content::text(lines.join("\n"))]);
+    result.structured_content = Some(serde_json::json!({
+        "approved": approved,
+        "accepted_hunks": accepted,
+        "rejected_hunks": rejected,
+        "accepted_files": accepted_files,
+        "rejected_files": rejected_files,
+        "untracked_files": untracked,
+        "comments": comments,
+        "summary": summary,
+        "accepted_patch": accepted_patch,
+    }));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::tools::review::FileVerdict;
+
+    #[test]
+    fn test_hunkless_files_need_a_whole_file_verdict() {
+        let files = parse_unified_diff(
+            "diff --git a/logo.png b/logo.png\nindex 1111111..2222222 100644\nBinary files a/logo.png and b/logo.png differ\n",
+        );
+        assert!(files[0].hunks.is_empty());
+
+        let approved = |verdict: &ReviewVerdict| {
+            build_review_result(&files, verdict, &[]).structured_content.unwrap()["approved"].clone()
+        };
+        assert_eq!(approved(&ReviewVerdict::default()), serde_json::json!(false));
+
+        let verdict = ReviewVerdict {
+            whole_files: vec![FileVerdict { path: "logo.png".to_string(), accepted: true }],
+            ..Default::default()
+        };
+        assert_eq!(approved(&verdict), serde_json::json!(true));
+    }
+}
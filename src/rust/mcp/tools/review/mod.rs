@@ -0,0 +1,12 @@
+//! 代码变更审查工具模块
+//!
+//! 在弹窗中按文件、按 hunk 展示 diff，用户逐块接受或拒绝并填写评论，返回结构化的审查结论
+
+pub mod diff;
+pub mod mcp;
+pub mod types;
+
+// 重新导出主要类型和功能
+pub use diff::{list_untracked_files, load_git_diff, parse_unified_diff, render_patch};
+pub use mcp::ReviewTool;
+pub use types::{DiffFile, DiffHunk, DiffReview, FileStatus, FileVerdict, ReviewVerdict};
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// 文件变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+}
+
+/// diff 中的一个代码块
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffHunk {
+    /// 审查结果中引用的编号，格式为 `文件序号.块序号`（从 1 开始）
+    pub id: String,
+    /// `@@ -a,b +c,d @@` 头部行
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// 带 ` `、`+`、`-` 前缀的原始行
+    pub lines: Vec<String>,
+}
+
+/// diff 中的一个文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffFile {
+    /// 变更后的路径（删除的文件为原路径）
+    pub path: String,
+    /// 变更前的路径，新增文件为 `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    pub status: FileStatus,
+    /// 二进制文件没有 hunk，只能整体审查
+    #[serde(default)]
+    pub binary: bool,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// 弹窗中展示的审查内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffReview {
+    pub project_path: String,
+    /// 变更来源说明，如 `HEAD~1..HEAD` 或 `unified diff`
+    pub source: String,
+    pub files: Vec<DiffFile>,
+}
+
+/// 单个 hunk 的审查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HunkVerdict {
+    pub id: String,
+    pub accepted: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// 没有 hunk 的文件（二进制、纯重命名、仅权限变更）的整体审查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileVerdict {
+    pub path: String,
+    pub accepted: bool,
+}
+
+/// 文件级评论
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileComment {
+    pub path: String,
+    pub comment: String,
+}
+
+/// 弹窗返回的审查结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewVerdict {
+    #[serde(default)]
+    pub hunks: Vec<HunkVerdict>,
+    #[serde(default)]
+    pub files: Vec<FileComment>,
+    /// 没有 hunk 的文件的整体审查结果
+    #[serde(default)]
+    pub whole_files: Vec<FileVerdict>,
+    /// 整体评论
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
@@ -1,16 +1,173 @@
 use chrono;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
 pub struct ZhiRequest {
-    #[schemars(description = "要显示给用户的消息")]
+    #[schemars(description = "要显示给用户的消息；批量提问时作为总体说明")]
+    #[serde(default)]
     pub message: String,
-    #[schemars(description = "预定义的选项列表（可选）")]
+    #[schemars(description = "预定义的选项列表（可选），每项为字符串或 {label, description, value, recommended} 对象")]
     #[serde(default)]
-    pub predefined_options: Vec<String>,
+    pub predefined_options: Vec<PredefinedOption>,
+    #[schemars(description = "选项的选择方式：single（单选）、multi（多选，默认）、none（仅展示不可选）")]
+    #[serde(default)]
+    pub selection_mode: SelectionMode,
     #[schemars(description = "消息是否为Markdown格式，默认为true")]
     #[serde(default = "default_is_markdown")]
     pub is_markdown: bool,
+    #[schemars(description = "等待用户回答的超时时间（秒，可选），超时后自动回复默认选项或继续提示词")]
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+    #[schemars(description = "超时后自动选择的选项（可选），未设置时回复继续提示词")]
+    #[serde(default)]
+    pub default_option: Option<String>,
+    #[schemars(description = "结构化表单字段（可选），用户填写的结果以 structured_content 返回")]
+    #[serde(default)]
+    pub fields: Vec<FormField>,
+    #[schemars(description = "当前项目路径（可选），用于记录交互历史，之后可通过 ji 的历史操作检索")]
+    #[serde(default)]
+    pub project_path: Option<String>,
+    #[schemars(description = "批量提问（可选）：多个相互独立的问题一次展示，回答以问题ID为键在 structured_content 中返回")]
+    #[serde(default)]
+    pub questions: Vec<ZhiQuestion>,
+}
+
+/// 批量提问中的单个问题
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct ZhiQuestion {
+    #[schemars(description = "问题ID，回答以此为键返回")]
+    pub id: String,
+    #[schemars(description = "问题内容")]
+    pub message: String,
+    #[schemars(description = "该问题的预定义选项（可选），格式同 predefined_options")]
+    #[serde(default)]
+    pub predefined_options: Vec<PredefinedOption>,
+    #[schemars(description = "该问题选项的选择方式，取值同 selection_mode")]
+    #[serde(default)]
+    pub selection_mode: SelectionMode,
+}
+
+/// 预定义选项：纯文本或带说明的对象
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum PredefinedOption {
+    Label(String),
+    Detailed {
+        #[schemars(description = "显示给用户的选项文本")]
+        label: String,
+        #[schemars(description = "选项说明（可选）")]
+        #[serde(default)]
+        description: Option<String>,
+        #[schemars(description = "选中后返回的值（可选，默认使用 label）")]
+        #[serde(default)]
+        value: Option<String>,
+        #[schemars(description = "是否为推荐选项，默认为false")]
+        #[serde(default)]
+        recommended: bool,
+    },
+}
+
+impl From<&str> for PredefinedOption {
+    fn from(label: &str) -> Self {
+        Self::Label(label.to_string())
+    }
+}
+
+/// 选项的选择方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionMode {
+    /// 单选，选择后即可提交
+    Single,
+    /// 多选
+    #[default]
+    Multi,
+    /// 选项仅作展示，不可选择
+    None,
+}
+
+/// 弹窗中展示的选项
+///
+/// 反序列化时兼容纯字符串，序列化时始终输出完整对象
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "PredefinedOption")]
+pub struct PopupOption {
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub value: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub recommended: bool,
+}
+
+impl PopupOption {
+    /// 由纯文本选项列表构造
+    pub fn from_labels(labels: &[String]) -> Vec<Self> {
+        labels.iter().map(|label| PredefinedOption::Label(label.clone()).into()).collect()
+    }
+}
+
+impl From<PredefinedOption> for PopupOption {
+    fn from(option: PredefinedOption) -> Self {
+        match option {
+            PredefinedOption::Label(label) => Self {
+                value: label.clone(),
+                label,
+                description: None,
+                recommended: false,
+            },
+            PredefinedOption::Detailed { label, description, value, recommended } => Self {
+                value: value.unwrap_or_else(|| label.clone()),
+                label,
+                description,
+                recommended,
+            },
+        }
+    }
+}
+
+/// 表单字段类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FormFieldType {
+    Text,
+    Number,
+    Boolean,
+    Select,
+    MultiSelect,
+    FilePath,
+}
+
+/// 表单字段
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FormField {
+    #[schemars(description = "字段名，作为返回结果中的键")]
+    pub name: String,
+    #[schemars(description = "显示给用户的标题（可选，默认使用字段名）")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[schemars(description = "字段类型：text, number, boolean, select, multi_select, file_path")]
+    #[serde(rename = "type")]
+    pub field_type: FormFieldType,
+    #[schemars(description = "字段说明（可选）")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[schemars(description = "select / multi_select 的可选项")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    #[schemars(description = "默认值（可选）")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    #[schemars(description = "是否必填，默认为false")]
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl FormField {
+    /// 显示给用户的标题
+    pub fn display_label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.name)
+    }
 }
 
 fn default_is_markdown() -> bool {
@@ -19,11 +176,16 @@ fn default_is_markdown() -> bool {
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct JiyiRequest {
-    #[schemars(description = "操作类型：记忆(添加记忆), 回忆(获取项目信息)")]
+    #[schemars(
+        description = "操作类型：记忆(添加记忆), 回忆(按相关度获取项目记忆), 更新(按ID修改记忆内容), 删除(按ID删除记忆), 整理(去重、归档过时记忆并调整分类), 历史(检索之前的寸止交互)"
+    )]
     pub action: String,
     #[schemars(description = "项目路径（必需）")]
     pub project_path: String,
-    #[schemars(description = "记忆内容（记忆操作时必需）")]
+    #[schemars(description = "记忆ID或其前缀（更新、删除操作时必需），可通过回忆操作查看")]
+    #[serde(default)]
+    pub id: String,
+    #[schemars(description = "记忆内容（记忆、更新操作时必需）")]
     #[serde(default)]
     pub content: String,
     #[schemars(
@@ -31,6 +193,79 @@ pub struct JiyiRequest {
     )]
     #[serde(default = "default_category")]
     pub category: String,
+    #[schemars(
+        description = "记忆范围：project(仅当前项目，默认), global(所有项目通用，如个人偏好)；回忆时项目范围会合并全局记忆，冲突时以项目记忆为准"
+    )]
+    #[serde(default = "default_scope")]
+    pub scope: String,
+    #[schemars(
+        description = "回忆操作的检索关键词（可选），按相关度返回记忆，规范类记忆始终排在最前；历史操作时为空格分隔的关键词"
+    )]
+    #[serde(default)]
+    pub query: Option<String>,
+    #[schemars(description = "历史操作只返回该时间之后的交互（可选）：RFC 3339 时间、YYYY-MM-DD 日期或 7d 形式的最近天数")]
+    #[serde(default)]
+    pub since: Option<String>,
+    #[schemars(description = "回忆操作限定的记忆分类（可选），取值同 category")]
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[schemars(description = "回忆操作输出的最大字符数（可选），默认 4000")]
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ShenRequest {
+    #[schemars(description = "项目路径（必需）")]
+    pub project_path: String,
+    #[schemars(description = "要审查的 unified diff（与 revision_range 二选一）")]
+    #[serde(default)]
+    pub diff: Option<String>,
+    #[schemars(description = "要审查的 git 修订范围，如 HEAD~1..HEAD（与 diff 二选一，都未提供时审查未提交的改动）")]
+    #[serde(default)]
+    pub revision_range: Option<String>,
+    #[schemars(description = "显示给用户的说明（可选）")]
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// 进度通知请求，同时作为桌面通知进程读取的请求文件
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct NotifyRequest {
+    #[schemars(description = "通知内容")]
+    pub message: String,
+    #[schemars(description = "通知标题（可选）")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[schemars(description = "通知级别：info, success, warning, error，默认为 info")]
+    #[serde(default)]
+    pub level: NotifyLevel,
+    #[schemars(description = "通知内容是否为Markdown格式，默认为true")]
+    #[serde(default = "default_is_markdown")]
+    pub is_markdown: bool,
+}
+
+/// 通知级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyLevel {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl NotifyLevel {
+    /// 通知前缀图标
+    pub fn icon(self) -> &'static str {
+        match self {
+            Self::Info => "ℹ️",
+            Self::Success => "✅",
+            Self::Warning => "⚠️",
+            Self::Error => "❌",
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -45,12 +280,192 @@ fn default_category() -> String {
     "context".to_string()
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+fn default_scope() -> String {
+    "project".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PopupRequest {
     pub id: String,
     pub message: String,
-    pub predefined_options: Option<Vec<String>>,
+    pub predefined_options: Option<Vec<PopupOption>>,
+    #[serde(default)]
+    pub selection_mode: SelectionMode,
     pub is_markdown: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_option: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<FormField>>,
+    /// 代码审查请求，弹窗展示 diff 而不是选项
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<crate::mcp::tools::review::DiffReview>,
+    /// 发起请求的客户端，用于在弹窗标题和 Telegram 消息中区分多个代理
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<ClientIdentity>,
+    /// 调用方项目路径
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_path: Option<String>,
+    /// 批量提问，弹窗逐个展示问题，回答以问题ID为键
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub questions: Option<Vec<PopupQuestion>>,
+}
+
+/// 批量提问中的单个问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PopupQuestion {
+    pub id: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub predefined_options: Vec<PopupOption>,
+    #[serde(default)]
+    pub selection_mode: SelectionMode,
+}
+
+impl From<ZhiQuestion> for PopupQuestion {
+    fn from(question: ZhiQuestion) -> Self {
+        Self {
+            id: question.id,
+            message: question.message,
+            predefined_options: question.predefined_options.into_iter().map(PopupOption::from).collect(),
+            selection_mode: question.selection_mode,
+        }
+    }
+}
+
+impl PopupQuestion {
+    /// 作为单独的请求提问，用于一次只能展示一个问题的渠道（Telegram、终端、elicitation）
+    ///
+    /// 沿用父请求的超时时间，批量提问时由 `ask_each_question` 改为剩余时间
+    pub fn as_request(&self, parent: &PopupRequest, index: usize, total: usize) -> PopupRequest {
+        let heading = format!("问题 {}/{}", index + 1, total);
+        PopupRequest {
+            id: format!("{}#{}", parent.id, self.id),
+            message: if parent.is_markdown {
+                format!("**{}**\n\n{}", heading, self.message)
+            } else {
+                format!("{}\n\n{}", heading, self.message)
+            },
+            predefined_options: (!self.predefined_options.is_empty()).then(|| self.predefined_options.clone()),
+            selection_mode: self.selection_mode,
+            is_markdown: parent.is_markdown,
+            timeout_seconds: parent.timeout_seconds,
+            default_option: None,
+            fields: None,
+            review: None,
+            client: parent.client.clone(),
+            project_path: parent.project_path.clone(),
+            questions: None,
+        }
+    }
+}
+
+/// 批量提问中单个问题的回答
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuestionAnswer {
+    #[serde(default)]
+    pub selected_options: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_input: Option<String>,
+}
+
+impl QuestionAnswer {
+    /// 从单个问题的响应中提取回答，用户取消时返回 `None`
+    pub fn from_response(response: &str) -> Option<Self> {
+        let response = response.trim();
+        if response == "CANCELLED" {
+            return None;
+        }
+
+        let answer = match serde_json::from_str::<McpResponse>(response) {
+            Ok(parsed) => Self {
+                selected_options: parsed.selected_options,
+                user_input: parsed.user_input,
+            },
+            Err(_) => Self {
+                selected_options: Vec::new(),
+                user_input: Some(response.to_string()),
+            },
+        };
+        Some(Self {
+            user_input: answer.user_input.map(|input| input.trim().to_string()).filter(|input| !input.is_empty()),
+            ..answer
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.selected_options.is_empty() && self.user_input.is_none()
+    }
+}
+
+/// 客户端身份，来自 MCP `initialize` 握手
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClientIdentity {
+    /// 连接级会话标识，每个连接各不相同
+    pub session_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl ClientIdentity {
+    /// 名称、版本和会话标识，如 `Cursor 1.2.0（会话 1a2b3c4d）`
+    pub fn describe(&self) -> String {
+        let name = match (&self.name, &self.version) {
+            (Some(name), Some(version)) => format!("{} {}", name, version),
+            (Some(name), None) => name.clone(),
+            _ => "未知客户端".to_string(),
+        };
+        let session: String = self.session_id.chars().take(8).collect();
+        format!("{}（会话 {}）", name, session)
+    }
+}
+
+impl PopupRequest {
+    /// 调用方标签，形如 `Cursor / foo`，客户端和项目都未知时返回 `None`
+    pub fn caller_label(&self) -> Option<String> {
+        let client = self.client.as_ref().and_then(|client| client.name.clone());
+        let project = self.project_path.as_deref().and_then(|path| {
+            path.trim_end_matches(['/', '\\'])
+                .rsplit(['/', '\\'])
+                .next()
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+        });
+        let parts: Vec<String> = client.into_iter().chain(project).collect();
+        (!parts.is_empty()).then(|| parts.join(" / "))
+    }
+
+    /// 等待回答的超时时间，未设置或为 0 时不超时
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        self.timeout_seconds
+            .filter(|secs| *secs > 0)
+            .map(std::time::Duration::from_secs)
+    }
+
+    /// 可供选择的选项，`none` 模式下为空
+    pub fn selectable_options(&self) -> &[PopupOption] {
+        match (self.selection_mode, &self.predefined_options) {
+            (SelectionMode::None, _) | (_, None) => &[],
+            (_, Some(options)) => options,
+        }
+    }
+
+    /// 超时后的自动回复
+    ///
+    /// 默认选项可以按 label 或 value 指定，返回时统一使用 value
+    pub fn timeout_response(&self) -> String {
+        let default_value = self.default_option.as_ref().map(|default| {
+            self.selectable_options()
+                .iter()
+                .find(|option| &option.label == default || &option.value == default)
+                .map(|option| option.value.clone())
+                .unwrap_or_else(|| default.clone())
+        });
+        build_timeout_response(default_value, Some(self.id.clone()))
+    }
 }
 
 /// 新的结构化响应数据格式
@@ -59,6 +474,10 @@ pub struct McpResponse {
     pub user_input: Option<String>,
     pub selected_options: Vec<String>,
     pub images: Vec<ImageAttachment>,
+    #[serde(default)]
+    pub form_values: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(default)]
+    pub answers: Option<std::collections::BTreeMap<String, QuestionAnswer>>,
     pub metadata: ResponseMetadata,
 }
 
@@ -127,6 +546,33 @@ pub fn build_send_response(
     response.to_string()
 }
 
+/// 构建带表单结果的发送响应
+pub fn build_form_response(
+    form_values: serde_json::Map<String, serde_json::Value>,
+    user_input: Option<String>,
+    selected_options: Vec<String>,
+    images: Vec<ImageAttachment>,
+    request_id: Option<String>,
+    source: &str,
+) -> String {
+    let mut response = build_mcp_response(user_input, selected_options, images, request_id, source);
+    response["form_values"] = serde_json::Value::Object(form_values);
+    response.to_string()
+}
+
+/// 构建批量提问的响应，回答以问题ID为键
+pub fn build_batch_response(
+    answers: std::collections::BTreeMap<String, QuestionAnswer>,
+    user_input: Option<String>,
+    images: Vec<ImageAttachment>,
+    request_id: Option<String>,
+    source: &str,
+) -> String {
+    let mut response = build_mcp_response(user_input, vec![], images, request_id, source);
+    response["answers"] = serde_json::json!(answers);
+    response.to_string()
+}
+
 /// 构建继续操作的响应
 pub fn build_continue_response(request_id: Option<String>, source: &str) -> String {
     // 动态获取继续提示词
@@ -139,3 +585,13 @@ pub fn build_continue_response(request_id: Option<String>, source: &str) -> Stri
     let response = build_mcp_response(Some(continue_prompt), vec![], vec![], request_id, source);
     response.to_string()
 }
+
+/// 构建超时自动回复的响应
+///
+/// 有默认选项时选中该选项，否则回复继续提示词
+pub fn build_timeout_response(default_option: Option<String>, request_id: Option<String>) -> String {
+    match default_option.filter(|option| !option.trim().is_empty()) {
+        Some(option) => build_send_response(None, vec![option], vec![], request_id, "timeout"),
+        None => build_continue_response(request_id, "timeout"),
+    }
+}
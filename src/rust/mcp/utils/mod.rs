@@ -1,5 +1,7 @@
 pub mod common;
 pub mod errors;
+pub mod progress;
 
 pub use common::*;
 pub use errors::*;
+pub use progress::*;
@@ -0,0 +1,54 @@
+/// MCP 进度通知工具模块
+///
+/// 为耗时的工具调用向客户端发送 `notifications/progress`
+
+use rmcp::{
+    RoleServer,
+    model::{ProgressNotificationParam, ProgressToken},
+    service::{Peer, RequestContext},
+};
+
+use crate::log_debug;
+
+/// 进度上报器
+///
+/// 仅当调用方在请求的 `_meta` 中提供了 progressToken 时才会真正发送通知
+#[derive(Clone, Default)]
+pub struct ProgressReporter {
+    target: Option<(Peer<RoleServer>, ProgressToken)>,
+}
+
+impl ProgressReporter {
+    /// 不发送任何通知的上报器（用于 Tauri 命令等非 MCP 调用）
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// 从请求上下文创建上报器
+    pub fn from_context(context: &RequestContext<RoleServer>) -> Self {
+        let target = context
+            .meta
+            .get_progress_token()
+            .map(|token| (context.peer.clone(), token));
+        Self { target }
+    }
+
+    /// 发送一次进度通知，progress 需单调递增
+    pub async fn report(&self, progress: f64, total: Option<f64>, message: impl Into<String>) {
+        let Some((peer, token)) = &self.target else {
+            return;
+        };
+
+        let param = ProgressNotificationParam {
+            progress_token: token.clone(),
+            progress,
+            total,
+            message: Some(message.into()),
+        };
+
+        // 进度通知失败不影响工具执行
+        if let Err(e) = peer.notify_progress(param).await {
+            log_debug!("发送进度通知失败: {}", e);
+        }
+    }
+}
@@ -11,6 +11,7 @@ use teloxide::{
 };
 
 use super::markdown::process_telegram_markdown;
+use crate::mcp::types::{PopupOption, SelectionMode};
 
 /// Telegram事件类型
 #[derive(Debug, Clone, Serialize)]
@@ -26,6 +27,15 @@ pub enum TelegramEvent {
     SendPressed,
 }
 
+/// 选项按钮回调
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionCallback {
+    /// 多选模式下切换选项
+    Toggle(String),
+    /// 单选模式下选择选项，立即提交
+    Select(String),
+}
+
 /// Telegram Bot 核心功能
 pub struct TelegramCore {
     pub bot: Bot,
@@ -87,26 +97,31 @@ impl TelegramCore {
         Ok(())
     }
 
-    /// 发送选项消息（消息一）
+    /// 发送选项消息（消息一），返回消息ID
+    ///
+    /// 按钮只能显示选项文本，选项说明附加在消息末尾；`none` 模式下不显示按钮
     pub async fn send_options_message(
         &self,
         message: &str,
-        predefined_options: &[String],
+        options: &[PopupOption],
+        selection_mode: SelectionMode,
         is_markdown: bool,
-    ) -> Result<()> {
+    ) -> Result<Option<i32>> {
+        let message = append_option_descriptions(message, options, selection_mode, is_markdown);
+
         // 处理消息内容
         let processed_message = if is_markdown {
-            process_telegram_markdown(message)
+            process_telegram_markdown(&message)
         } else {
-            message.to_string()
+            message
         };
 
         // 创建消息发送请求
         let mut send_request = self.bot.send_message(self.chat_id, processed_message);
 
-        // 只有当有预定义选项时才添加inline keyboard
-        if !predefined_options.is_empty() {
-            let inline_keyboard = Self::create_inline_keyboard(predefined_options, &[])?;
+        // 只有当有可选择的选项时才添加inline keyboard
+        if !options.is_empty() && selection_mode != SelectionMode::None {
+            let inline_keyboard = Self::create_inline_keyboard(options, &[], selection_mode)?;
             send_request = send_request.reply_markup(inline_keyboard);
         }
 
@@ -116,7 +131,7 @@ impl TelegramCore {
         }
 
         match send_request.await {
-            Ok(_) => Ok(()),
+            Ok(msg) => Ok(Some(msg.id.0)),
             Err(e) => {
                 let error_str = e.to_string();
 
@@ -125,8 +140,8 @@ impl TelegramCore {
                 let has_ok_true = error_str.contains("\\\"ok\\\":true");
 
                 if has_parsing_json && has_ok_true {
-                    // 消息实际发送成功
-                    Ok(())
+                    // 消息实际发送成功，但无法得知消息ID
+                    Ok(None)
                 } else {
                     Err(anyhow::anyhow!("发送选项消息失败: {}", e))
                 }
@@ -163,23 +178,33 @@ impl TelegramCore {
     }
 
     /// 创建inline keyboard
+    ///
+    /// 单选模式下按钮点击即提交，多选模式下按钮切换选中状态。
+    /// Telegram 限制 callback_data 最多 64 字节，按钮只携带选项序号，收到回调时再换回选项值
     pub fn create_inline_keyboard(
-        predefined_options: &[String],
+        options: &[PopupOption],
         selected_options: &[String],
+        selection_mode: SelectionMode,
     ) -> Result<InlineKeyboardMarkup> {
         let mut keyboard_rows = Vec::new();
+        let prefix = match selection_mode {
+            SelectionMode::Single => "select:",
+            _ => "toggle:",
+        };
 
         // 添加选项按钮（每行最多2个）
-        for chunk in predefined_options.chunks(2) {
+        for (row_index, chunk) in options.chunks(2).enumerate() {
             let mut row = Vec::new();
-            for option in chunk {
-                let callback_data = format!("toggle:{}", option);
-                // 根据选中状态显示按钮
-                let button_text = if selected_options.contains(option) {
-                    format!("✅ {}", option)
-                } else {
-                    option.to_string()
-                };
+            for (column, option) in chunk.iter().enumerate() {
+                let callback_data = format!("{}{}", prefix, row_index * 2 + column);
+                // 根据选中状态和推荐标记显示按钮
+                let mut button_text = option.label.clone();
+                if option.recommended {
+                    button_text = format!("⭐ {}", button_text);
+                }
+                if selected_options.contains(&option.value) {
+                    button_text = format!("✅ {}", button_text);
+                }
 
                 row.push(InlineKeyboardButton::callback(button_text, callback_data));
             }
@@ -207,10 +232,11 @@ impl TelegramCore {
     pub async fn update_inline_keyboard(
         &self,
         message_id: i32,
-        predefined_options: &[String],
+        options: &[PopupOption],
         selected_options: &[String],
+        selection_mode: SelectionMode,
     ) -> Result<()> {
-        let new_keyboard = Self::create_inline_keyboard(predefined_options, selected_options)?;
+        let new_keyboard = Self::create_inline_keyboard(options, selected_options, selection_mode)?;
 
         match self
             .bot
@@ -228,11 +254,14 @@ impl TelegramCore {
 }
 
 /// 处理callback query的通用函数（不发送事件，由调用方处理）
+///
+/// 按钮携带的是选项序号，这里按 `options` 换回选项值，序号越界的回调被忽略
 pub async fn handle_callback_query(
     bot: &Bot,
     callback_query: &CallbackQuery,
     target_chat_id: ChatId,
-) -> ResponseResult<Option<String>> {
+    options: &[PopupOption],
+) -> ResponseResult<Option<OptionCallback>> {
     // 检查是否是目标聊天
     if let Some(message) = &callback_query.message {
         if message.chat().id != target_chat_id {
@@ -240,19 +269,60 @@ pub async fn handle_callback_query(
         }
     }
 
-    let mut toggled_option = None;
-
-    if let Some(data) = &callback_query.data {
-        if data.starts_with("toggle:") {
-            let option = data.strip_prefix("toggle:").unwrap().to_string();
-            toggled_option = Some(option);
+    let option_value = |index: &str| {
+        index
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| options.get(index))
+            .map(|option| option.value.clone())
+    };
+    let option_callback = callback_query.data.as_deref().and_then(|data| {
+        if let Some(index) = data.strip_prefix("toggle:") {
+            option_value(index).map(OptionCallback::Toggle)
+        } else {
+            data.strip_prefix("select:")
+                .and_then(option_value)
+                .map(OptionCallback::Select)
         }
-    }
+    });
 
     // 回答callback query
     bot.answer_callback_query(&callback_query.id).await?;
 
-    Ok(toggled_option)
+    Ok(option_callback)
+}
+
+/// 将选项说明附加到消息末尾
+///
+/// 没有说明且可通过按钮选择时保持消息不变
+fn append_option_descriptions(
+    message: &str,
+    options: &[PopupOption],
+    selection_mode: SelectionMode,
+    is_markdown: bool,
+) -> String {
+    let has_descriptions = options.iter().any(|option| option.description.is_some());
+    if options.is_empty() || (selection_mode != SelectionMode::None && !has_descriptions) {
+        return message.to_string();
+    }
+
+    let mut lines = vec![message.to_string(), String::new()];
+    for option in options {
+        let label = if is_markdown {
+            format!("**{}**", option.label)
+        } else {
+            option.label.clone()
+        };
+        let mut line = format!("• {}", label);
+        if option.recommended {
+            line.push_str(" ⭐推荐");
+        }
+        if let Some(description) = &option.description {
+            line.push_str(&format!("：{}", description));
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
 }
 
 /// 处理文本消息的通用函数（不发送事件，由调用方处理）
@@ -1,10 +1,18 @@
 use anyhow::Result;
 use std::collections::HashSet;
 use teloxide::prelude::*;
-
-use crate::config::load_standalone_config;
-use crate::mcp::types::{build_continue_response, build_send_response, PopupRequest};
-use crate::telegram::{handle_callback_query, handle_text_message, TelegramCore, TelegramEvent};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, KeyboardRemove, MessageId};
+
+use crate::config::{load_standalone_config, TelegramConfig};
+use crate::mcp::handlers::{ask_each_question, parse_form_text};
+use crate::mcp::types::{
+    build_continue_response, build_form_response, build_send_response, FormField, FormFieldType, PopupOption,
+    PopupRequest, SelectionMode,
+};
+use crate::telegram::{
+    handle_callback_query, handle_text_message, process_telegram_markdown, OptionCallback, TelegramCore,
+    TelegramEvent,
+};
 use crate::log_important;
 
 /// 处理纯Telegram模式的MCP请求（不启动GUI）
@@ -15,19 +23,29 @@ pub async fn handle_telegram_only_mcp_request(request_file: &str) -> Result<()>
 
     // 加载完整配置
     let app_config = load_standalone_config()?;
-    let telegram_config = &app_config.telegram_config;
-
-    if !telegram_config.enabled {
-        log_important!(warn, "Telegram未启用，无法处理请求");
+    let Some(core) = telegram_core_from_config(&app_config.telegram_config)? else {
+        log_important!(warn, "Telegram未启用或配置不完整，无法处理请求");
         return Ok(());
-    }
+    };
 
-    if telegram_config.bot_token.trim().is_empty() || telegram_config.chat_id.trim().is_empty() {
-        log_important!(warn, "Telegram配置不完整");
-        return Ok(());
+    send_telegram_request(&core, &request).await?;
+    let response = wait_for_telegram_answer(&core, &request, |_| {}).await?;
+
+    // 输出JSON响应到stdout（MCP协议要求）
+    println!("{}", response);
+    Ok(())
+}
+
+/// 根据配置创建Telegram核心实例，未启用或配置不完整时返回 `None`
+pub fn telegram_core_from_config(telegram_config: &TelegramConfig) -> Result<Option<TelegramCore>> {
+    if !telegram_config.enabled
+        || telegram_config.bot_token.trim().is_empty()
+        || telegram_config.chat_id.trim().is_empty()
+    {
+        return Ok(None);
     }
 
-    // 创建Telegram核心实例，使用配置中的API URL
+    // 使用默认API URL时传递None，否则传递自定义URL
     let api_url = if telegram_config.api_base_url == crate::constants::telegram::API_BASE_URL {
         None
     } else {
@@ -39,30 +57,127 @@ pub async fn handle_telegram_only_mcp_request(request_file: &str) -> Result<()>
         telegram_config.chat_id.clone(),
         api_url,
     )?;
+    Ok(Some(core))
+}
 
-    // 发送消息到Telegram
+/// 发送MCP请求消息到Telegram，返回选项消息ID
+pub async fn send_telegram_request(core: &TelegramCore, request: &PopupRequest) -> Result<Option<i32>> {
     let predefined_options = request.predefined_options.clone().unwrap_or_default();
 
+    // 多个代理同时提问时，在消息头部标明调用方
+    let message = match request.caller_label() {
+        Some(label) => format!("🤖 {}\n\n{}", label, request.message),
+        None => request.message.clone(),
+    };
+
+    // 批量提问：先发送总体说明，问题在等待回答时逐个发送
+    if let Some(questions) = request.questions.as_deref() {
+        let intro = format!("{}\n\n📋 共 {} 个问题，将逐个发送", message, questions.len());
+        if request.is_markdown {
+            core.send_message_with_markdown(&process_telegram_markdown(&intro), true).await?;
+        } else {
+            core.send_message(&intro).await?;
+        }
+        return Ok(None);
+    }
+
     // 发送选项消息
-    core.send_options_message(&request.message, &predefined_options, request.is_markdown)
+    let options_message_id = core
+        .send_options_message(
+            &message,
+            &predefined_options,
+            request.selection_mode,
+            request.is_markdown,
+        )
         .await?;
 
+    // 表单请求：说明需要填写的字段，用户以 `字段: 值` 的形式逐行回复
+    if let Some(fields) = request.fields.as_deref().filter(|f| !f.is_empty()) {
+        core.send_message(&build_form_instructions(fields)).await?;
+    }
+
     // 短暂延迟确保消息顺序
     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
     // 发送操作消息（假设启用继续回复）
     core.send_operation_message(true).await?;
 
-    // 启动消息监听循环
-    start_telegram_mcp_listener(core, request, predefined_options).await
+    Ok(options_message_id)
+}
+
+/// 等待用户在Telegram中作答，返回与弹窗相同格式的响应
+///
+/// 请求设置了超时时，超时后返回自动回复并通知用户。批量提问时逐条发送问题，
+/// 每发送一条就以其选项消息ID调用 `on_question_sent`，便于撤回当前问题
+pub async fn wait_for_telegram_answer(
+    core: &TelegramCore,
+    request: &PopupRequest,
+    on_question_sent: impl Fn(Option<i32>) + Sync,
+) -> Result<String> {
+    let Some(questions) = request.questions.as_deref() else {
+        return wait_for_single_answer(core, request).await;
+    };
+
+    // 批量提问：逐条发送问题，所有问题共用超时时间
+    let on_question_sent = &on_question_sent;
+    ask_each_question(request, questions, "telegram", |question_request| async move {
+        // 总体说明已经标明调用方
+        let question_request = PopupRequest {
+            client: None,
+            project_path: None,
+            ..question_request
+        };
+        on_question_sent(send_telegram_request(core, &question_request).await?);
+        wait_for_single_answer(core, &question_request).await
+    })
+    .await
+}
+
+/// 等待单个问题的回答
+async fn wait_for_single_answer(core: &TelegramCore, request: &PopupRequest) -> Result<String> {
+    let predefined_options = request.selectable_options().to_vec();
+    let listener = start_telegram_mcp_listener(core, request, predefined_options);
+    let Some(timeout) = request.timeout() else {
+        return listener.await;
+    };
+
+    match tokio::time::timeout(timeout, listener).await {
+        Ok(result) => result,
+        Err(_) => Ok(handle_timeout(core, request).await),
+    }
+}
+
+/// 其他渠道已作答时撤回Telegram中的请求
+///
+/// 将选项消息的按钮替换为已作答提示，并移除操作键盘
+pub async fn withdraw_telegram_request(core: &TelegramCore, options_message_id: Option<i32>, answered_by: &str) {
+    let notice = format!("☑️ 已在{}回答", answered_by);
+
+    if let Some(message_id) = options_message_id {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            notice.clone(),
+            "answered",
+        )]]);
+        let _ = core
+            .bot
+            .edit_message_reply_markup(core.chat_id, MessageId(message_id))
+            .reply_markup(keyboard)
+            .await;
+    }
+
+    let _ = core
+        .bot
+        .send_message(core.chat_id, notice)
+        .reply_markup(KeyboardRemove::new())
+        .await;
 }
 
 /// 启动Telegram MCP消息监听循环
 async fn start_telegram_mcp_listener(
-    core: TelegramCore,
-    request: PopupRequest,
-    predefined_options: Vec<String>,
-) -> Result<()> {
+    core: &TelegramCore,
+    request: &PopupRequest,
+    predefined_options: Vec<PopupOption>,
+) -> Result<String> {
     let mut offset = 0i32;
     let mut selected_options: HashSet<String> = HashSet::new();
     let mut user_input = String::new();
@@ -84,31 +199,34 @@ async fn start_telegram_mcp_listener(
 
                     match update.kind {
                         teloxide::types::UpdateKind::CallbackQuery(callback_query) => {
-                            if let Err(e) = handle_callback_query_update(
-                                &core,
+                            match handle_callback_query_update(
+                                core,
                                 &callback_query,
                                 &predefined_options,
                                 &mut selected_options,
                                 &mut options_message_id,
+                                &user_input,
+                                request,
                             ).await {
-                                log_important!(warn, "处理callback query失败: {}", e);
+                                Ok(Some(response)) => return Ok(response),
+                                Ok(None) => {}
+                                Err(e) => log_important!(warn, "处理callback query失败: {}", e),
                             }
                         }
                         teloxide::types::UpdateKind::Message(message) => {
                             // 处理选项消息ID识别
-                            if let Err(e) = handle_message_update(
-                                &core,
+                            match handle_message_update(
+                                core,
                                 &message,
                                 &predefined_options,
                                 &mut options_message_id,
                                 &mut user_input,
                                 &selected_options,
-                                &request,
+                                request,
                             ).await {
-                                if let Some(_result) = e.downcast_ref::<ProcessingComplete>() {
-                                    return Ok(());
-                                }
-                                log_important!(warn, "处理消息失败: {}", e);
+                                Ok(Some(response)) => return Ok(response),
+                                Ok(None) => {}
+                                Err(e) => log_important!(warn, "处理消息失败: {}", e),
                             }
                         }
                         _ => {}
@@ -125,17 +243,19 @@ async fn start_telegram_mcp_listener(
     }
 }
 
-/// 处理callback query更新
+/// 处理callback query更新，单选模式下选择选项时返回响应
 async fn handle_callback_query_update(
     core: &TelegramCore,
     callback_query: &teloxide::types::CallbackQuery,
-    predefined_options: &[String],
+    predefined_options: &[PopupOption],
     selected_options: &mut HashSet<String>,
     options_message_id: &mut Option<i32>,
-) -> Result<()> {
+    user_input: &str,
+    request: &PopupRequest,
+) -> Result<Option<String>> {
     // 只有当有预定义选项时才处理 callback queries
     if predefined_options.is_empty() {
-        return Ok(());
+        return Ok(None);
     }
 
     // 从callback_query中提取消息ID
@@ -145,49 +265,75 @@ async fn handle_callback_query_update(
         }
     }
 
-    if let Ok(Some(option)) = handle_callback_query(&core.bot, callback_query, core.chat_id).await {
-        // 切换选项状态
-        if selected_options.contains(&option) {
-            selected_options.remove(&option);
-        } else {
-            selected_options.insert(option.clone());
+    match handle_callback_query(&core.bot, callback_query, core.chat_id, predefined_options).await {
+        Ok(Some(OptionCallback::Select(option))) if request.selection_mode == SelectionMode::Single => {
+            // 单选模式：点击即提交，已输入的文本一并发送
+            if let Some(msg_id) = *options_message_id {
+                let _ = core
+                    .update_inline_keyboard(msg_id, predefined_options, &[option.clone()], request.selection_mode)
+                    .await;
+            }
+            let selected = HashSet::from([option]);
+            return Ok(Some(handle_send_pressed(core, &selected, user_input, request).await));
         }
+        Ok(Some(OptionCallback::Toggle(option))) => toggle_option(
+            core,
+            predefined_options,
+            selected_options,
+            *options_message_id,
+            request.selection_mode,
+            option,
+        )
+        .await,
+        _ => {}
+    }
 
-        // 更新按钮状态
-        if let Some(msg_id) = *options_message_id {
-            let selected_vec: Vec<String> = selected_options.iter().cloned().collect();
-            let _ = core
-                .update_inline_keyboard(msg_id, predefined_options, &selected_vec)
-                .await;
-        }
+    Ok(None)
+}
+
+/// 多选模式下切换选项并更新按钮状态
+async fn toggle_option(
+    core: &TelegramCore,
+    predefined_options: &[PopupOption],
+    selected_options: &mut HashSet<String>,
+    options_message_id: Option<i32>,
+    selection_mode: SelectionMode,
+    option: String,
+) {
+    if !selected_options.remove(&option) {
+        selected_options.insert(option);
     }
 
-    Ok(())
+    // 更新按钮状态
+    if let Some(msg_id) = options_message_id {
+        let selected_vec: Vec<String> = selected_options.iter().cloned().collect();
+        let _ = core
+            .update_inline_keyboard(msg_id, predefined_options, &selected_vec, selection_mode)
+            .await;
+    }
 }
 
 /// 处理消息更新
 async fn handle_message_update(
     core: &TelegramCore,
     message: &teloxide::types::Message,
-    predefined_options: &[String],
+    predefined_options: &[PopupOption],
     options_message_id: &mut Option<i32>,
     user_input: &mut String,
     selected_options: &HashSet<String>,
     request: &PopupRequest,
-) -> Result<()> {
+) -> Result<Option<String>> {
     // 识别选项消息ID
     identify_options_message_id(message, predefined_options, options_message_id);
 
-    // 处理文本消息事件
+    // 处理文本消息事件，发送或继续时返回响应
     if let Ok(Some(event)) = handle_text_message(message, core.chat_id, None).await {
         match event {
             TelegramEvent::SendPressed => {
-                handle_send_pressed(core, selected_options, user_input, request).await?;
-                return Err(ProcessingComplete.into());
+                return Ok(Some(handle_send_pressed(core, selected_options, user_input, request).await));
             }
             TelegramEvent::ContinuePressed => {
-                handle_continue_pressed(core, request).await?;
-                return Err(ProcessingComplete.into());
+                return Ok(Some(handle_continue_pressed(core, request).await));
             }
             TelegramEvent::TextUpdated { text } => {
                 *user_input = text;
@@ -196,13 +342,13 @@ async fn handle_message_update(
         }
     }
 
-    Ok(())
+    Ok(None)
 }
 
 /// 识别选项消息ID
 fn identify_options_message_id(
     message: &teloxide::types::Message,
-    predefined_options: &[String],
+    predefined_options: &[PopupOption],
     options_message_id: &mut Option<i32>,
 ) {
     // 只有当有预定义选项时才检查 inline keyboard
@@ -217,7 +363,7 @@ fn identify_options_message_id(
         for row in &inline_keyboard.inline_keyboard {
             for button in row {
                 if let teloxide::types::InlineKeyboardButtonKind::CallbackData(callback_data) = &button.kind {
-                    if callback_data.starts_with("toggle:") {
+                    if callback_data.starts_with("toggle:") || callback_data.starts_with("select:") {
                         contains_our_options = true;
                         break;
                     }
@@ -240,7 +386,7 @@ async fn handle_send_pressed(
     selected_options: &HashSet<String>,
     user_input: &str,
     request: &PopupRequest,
-) -> Result<()> {
+) -> String {
     // 使用统一的响应构建函数
     let selected_list: Vec<String> = selected_options.iter().cloned().collect();
 
@@ -250,16 +396,27 @@ async fn handle_send_pressed(
         Some(user_input.to_string())
     };
 
-    let response = build_send_response(
-        user_input_option,
-        selected_list.clone(),
-        vec![], // 无GUI模式下没有图片
-        Some(request.id.clone()),
-        "telegram",
-    );
-
-    // 输出JSON响应到stdout（MCP协议要求）
-    println!("{}", response);
+    let response = match request.fields.as_deref().filter(|f| !f.is_empty()) {
+        Some(fields) => {
+            // 从文本中提取表单值，其余内容作为补充说明
+            let (form_values, remaining) = parse_form_text(fields, user_input);
+            build_form_response(
+                form_values,
+                Some(remaining).filter(|text| !text.is_empty()),
+                selected_list.clone(),
+                vec![],
+                Some(request.id.clone()),
+                "telegram",
+            )
+        }
+        None => build_send_response(
+            user_input_option,
+            selected_list.clone(),
+            vec![], // 无GUI模式下没有图片
+            Some(request.id.clone()),
+            "telegram",
+        ),
+    };
 
     // 发送确认消息（使用统一的反馈消息生成函数）
     let feedback_message = crate::telegram::core::build_feedback_message(
@@ -269,23 +426,51 @@ async fn handle_send_pressed(
     );
     let _ = core.send_message(&feedback_message).await;
 
-    Ok(())
+    response
+}
+
+/// 生成表单填写说明
+fn build_form_instructions(fields: &[FormField]) -> String {
+    let mut lines = vec!["📝 请按 `字段: 值` 的格式逐行回复以下内容，然后点击发送：".to_string()];
+    for field in fields {
+        let type_hint = match field.field_type {
+            FormFieldType::Text => "文本".to_string(),
+            FormFieldType::Number => "数字".to_string(),
+            FormFieldType::Boolean => "是/否".to_string(),
+            FormFieldType::FilePath => "文件路径".to_string(),
+            FormFieldType::Select => format!("单选：{}", field.options.join(" / ")),
+            FormFieldType::MultiSelect => format!("多选，逗号分隔：{}", field.options.join(", ")),
+        };
+
+        let mut line = format!("• {}（{}）", field.name, type_hint);
+        if field.label.is_some() {
+            line = format!("• {} [{}]（{}）", field.name, field.display_label(), type_hint);
+        }
+        if field.required {
+            line.push_str(" *必填");
+        }
+        if let Some(default) = &field.default {
+            line.push_str(&format!("，默认 {}", default));
+        }
+        if let Some(description) = &field.description {
+            line.push_str(&format!("\n  {}", description));
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
 }
 
 /// 处理继续按钮按下
 async fn handle_continue_pressed(
     core: &TelegramCore,
     request: &PopupRequest,
-) -> Result<()> {
+) -> String {
     // 使用统一的继续响应构建函数
     let response = build_continue_response(
         Some(request.id.clone()),
         "telegram_continue",
     );
 
-    // 输出JSON响应到stdout（MCP协议要求）
-    println!("{}", response);
-
     // 发送确认消息（使用统一的反馈消息生成函数）
     let feedback_message = crate::telegram::core::build_feedback_message(
         &[],  // 继续操作没有选项
@@ -294,17 +479,16 @@ async fn handle_continue_pressed(
     );
     let _ = core.send_message(&feedback_message).await;
 
-    Ok(())
+    response
 }
 
-/// 处理完成标记（用于从监听循环中退出）
-#[derive(Debug)]
-struct ProcessingComplete;
+/// 处理等待超时
+async fn handle_timeout(core: &TelegramCore, request: &PopupRequest) -> String {
+    let feedback_message = match &request.default_option {
+        Some(option) => format!("⏰ 等待超时，已自动选择：{}", option),
+        None => "⏰ 等待超时，已自动回复继续".to_string(),
+    };
+    let _ = core.send_message(&feedback_message).await;
 
-impl std::fmt::Display for ProcessingComplete {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Processing complete")
-    }
+    request.timeout_response()
 }
-
-impl std::error::Error for ProcessingComplete {}
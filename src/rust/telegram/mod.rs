@@ -1,14 +1,15 @@
 pub mod commands;
 pub mod core;
-pub mod integration;
 pub mod markdown;
 pub mod mcp_handler;
 
 pub use commands::*;
 pub use core::{
-    handle_callback_query, handle_text_message, test_telegram_connection, TelegramCore,
-    TelegramEvent,
+    handle_callback_query, handle_text_message, test_telegram_connection, OptionCallback,
+    TelegramCore, TelegramEvent,
 };
-pub use integration::TelegramIntegration;
 pub use markdown::process_telegram_markdown;
-pub use mcp_handler::handle_telegram_only_mcp_request;
+pub use mcp_handler::{
+    handle_telegram_only_mcp_request, send_telegram_request, telegram_core_from_config,
+    wait_for_telegram_answer, withdraw_telegram_request,
+};
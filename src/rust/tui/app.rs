@@ -0,0 +1,349 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+use super::markdown::render_markdown;
+use crate::mcp::types::{PopupOption, PopupRequest, SelectionMode};
+
+/// 输入框高度（含边框）
+const INPUT_HEIGHT: u16 = 6;
+
+/// 选项列表最大高度（含边框）
+const MAX_OPTIONS_HEIGHT: u16 = 10;
+
+/// 用户在终端界面中的操作结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TuiOutcome {
+    Send {
+        user_input: Option<String>,
+        selected_options: Vec<String>,
+    },
+    Continue,
+    Cancel,
+    Timeout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Options,
+    Input,
+}
+
+/// 终端弹窗状态
+pub struct TuiApp {
+    title: String,
+    message: Text<'static>,
+    options: Vec<PopupOption>,
+    single: bool,
+    selected: Vec<bool>,
+    option_state: ListState,
+    input: InputBuffer,
+    focus: Focus,
+    scroll: u16,
+    deadline: Option<Instant>,
+}
+
+impl TuiApp {
+    pub fn new(request: &PopupRequest) -> Self {
+        let options = request.selectable_options().to_vec();
+        let mut message = if request.is_markdown {
+            render_markdown(&request.message)
+        } else {
+            Text::raw(request.message.clone())
+        };
+
+        // none 模式：选项仅作展示，附加在消息末尾
+        if let (SelectionMode::None, Some(options)) = (request.selection_mode, &request.predefined_options) {
+            message.lines.push(Line::default());
+            for option in options {
+                message.lines.push(option_line("• ", option));
+            }
+        }
+
+        let mut option_state = ListState::default();
+        if !options.is_empty() {
+            option_state.select(Some(0));
+        }
+
+        Self {
+            title: match request.caller_label() {
+                Some(label) => format!(" 寸止 · {} ", label),
+                None => " 寸止 ".to_string(),
+            },
+            message,
+            selected: vec![false; options.len()],
+            focus: if options.is_empty() { Focus::Input } else { Focus::Options },
+            options,
+            single: request.selection_mode == SelectionMode::Single,
+            option_state,
+            input: InputBuffer::default(),
+            scroll: 0,
+            deadline: request.timeout().map(|timeout| Instant::now() + timeout),
+        }
+    }
+
+    /// 距离超时的剩余时间，未设置超时时返回 `None`
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// 处理按键，返回 `Some` 表示交互结束
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<TuiOutcome> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Esc => return Some(TuiOutcome::Cancel),
+            KeyCode::Char('c') if ctrl => return Some(TuiOutcome::Cancel),
+            KeyCode::Char('s') if ctrl => return Some(self.build_send()),
+            KeyCode::Char('n') if ctrl => return Some(TuiOutcome::Continue),
+            KeyCode::Tab | KeyCode::BackTab if !self.options.is_empty() => {
+                self.focus = match self.focus {
+                    Focus::Options => Focus::Input,
+                    Focus::Input => Focus::Options,
+                };
+            }
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(5),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(5),
+            _ => match self.focus {
+                Focus::Options => self.handle_option_key(key),
+                Focus::Input => self.handle_input_key(key),
+            },
+        }
+
+        None
+    }
+
+    fn handle_option_key(&mut self, key: KeyEvent) {
+        let current = self.option_state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.option_state.select(Some(current.saturating_sub(1)));
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.option_state.select(Some((current + 1).min(self.options.len() - 1)));
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                let toggled = !self.selected.get(current).copied().unwrap_or(false);
+                if self.single {
+                    // 单选：选中一项时取消其他选项
+                    self.selected.iter_mut().for_each(|selected| *selected = false);
+                }
+                if let Some(selected) = self.selected.get_mut(current) {
+                    *selected = toggled;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_input_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char(c) => self.input.insert(c),
+            KeyCode::Enter => self.input.insert('\n'),
+            KeyCode::Backspace => self.input.backspace(),
+            KeyCode::Delete => self.input.delete(),
+            KeyCode::Left => self.input.left(),
+            KeyCode::Right => self.input.right(),
+            KeyCode::Home => self.input.home(),
+            KeyCode::End => self.input.end(),
+            _ => {}
+        }
+    }
+
+    fn build_send(&self) -> TuiOutcome {
+        let selected_options: Vec<String> = self
+            .options
+            .iter()
+            .zip(&self.selected)
+            .filter(|(_, selected)| **selected)
+            .map(|(option, _)| option.value.clone())
+            .collect();
+
+        let text = self.input.text.trim();
+        let user_input = if !text.is_empty() {
+            Some(text.to_string())
+        } else if selected_options.is_empty() {
+            // 与弹窗一致：没有任何内容时视为确认继续
+            Some("用户确认继续".to_string())
+        } else {
+            None
+        };
+
+        TuiOutcome::Send { user_input, selected_options }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        let options_height = if self.options.is_empty() {
+            0
+        } else {
+            (self.options.len() as u16 + 2).min(MAX_OPTIONS_HEIGHT)
+        };
+
+        let [message_area, options_area, input_area, help_area] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(options_height),
+            Constraint::Length(INPUT_HEIGHT),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(
+            Paragraph::new(self.message.clone())
+                .block(Block::bordered().title(self.title.as_str()))
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0)),
+            message_area,
+        );
+
+        if !self.options.is_empty() {
+            self.render_options(frame, options_area);
+        }
+        self.render_input(frame, input_area);
+
+        let mut help = "Tab 切换  空格 选择  Ctrl+S 发送  Ctrl+N 继续  Esc 取消  PgUp/PgDn 滚动".to_string();
+        if let Some(remaining) = self.remaining() {
+            help.push_str(&format!("  ⏱ {} 秒后自动回复", remaining.as_secs()));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::styled(help, Style::default().fg(Color::DarkGray))),
+            help_area,
+        );
+    }
+
+    fn render_options(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .options
+            .iter()
+            .zip(&self.selected)
+            .map(|(option, selected)| {
+                let marker = match (self.single, *selected) {
+                    (true, true) => "(•) ",
+                    (true, false) => "( ) ",
+                    (false, true) => "[x] ",
+                    (false, false) => "[ ] ",
+                };
+                ListItem::new(option_line(marker, option))
+            })
+            .collect();
+        let title = if self.single { " 选项（单选） " } else { " 选项（可多选） " };
+
+        let highlight = if self.focus == Focus::Options {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
+
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(focus_block(title, self.focus == Focus::Options))
+                .highlight_style(highlight),
+            area,
+            &mut self.option_state,
+        );
+    }
+
+    fn render_input(&self, frame: &mut Frame, area: Rect) {
+        let focused = self.focus == Focus::Input;
+        let (column, row) = self.input.cursor_position();
+        let visible_rows = area.height.saturating_sub(2);
+        let scroll = row.saturating_sub(visible_rows.saturating_sub(1));
+
+        frame.render_widget(
+            Paragraph::new(self.input.text.as_str())
+                .block(focus_block(" 补充说明 ", focused))
+                .scroll((scroll, 0)),
+            area,
+        );
+
+        if focused {
+            let x = area.x + 1 + column.min(area.width.saturating_sub(3));
+            let y = area.y + 1 + row - scroll;
+            frame.set_cursor_position((x, y));
+        }
+    }
+}
+
+/// 选项行：标记、文本、推荐标识和说明
+fn option_line(marker: &str, option: &PopupOption) -> Line<'static> {
+    let mut spans = vec![Span::raw(format!("{}{}", marker, option.label))];
+    if option.recommended {
+        spans.push(Span::styled(" ⭐推荐", Style::default().fg(Color::Yellow)));
+    }
+    if let Some(description) = &option.description {
+        spans.push(Span::styled(format!("  {}", description), Style::default().fg(Color::DarkGray)));
+    }
+    Line::from(spans)
+}
+
+fn focus_block(title: &'static str, focused: bool) -> Block<'static> {
+    let style = if focused {
+        Style::default().fg(Color::Blue)
+    } else {
+        Style::default()
+    };
+    Block::bordered().title(title).border_style(style)
+}
+
+/// 多行文本输入缓冲区，光标为字节偏移
+#[derive(Default)]
+struct InputBuffer {
+    text: String,
+    cursor: usize,
+}
+
+impl InputBuffer {
+    fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    fn backspace(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+            self.text.remove(self.cursor);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.cursor < self.text.len() {
+            self.text.remove(self.cursor);
+        }
+    }
+
+    fn left(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+        }
+    }
+
+    fn right(&mut self) {
+        if let Some(c) = self.text[self.cursor..].chars().next() {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    fn home(&mut self) {
+        self.cursor = self.text[..self.cursor].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    }
+
+    fn end(&mut self) {
+        self.cursor = self.text[self.cursor..]
+            .find('\n')
+            .map(|i| self.cursor + i)
+            .unwrap_or(self.text.len());
+    }
+
+    /// 光标所在的显示列宽和行号
+    fn cursor_position(&self) -> (u16, u16) {
+        let before = &self.text[..self.cursor];
+        let row = before.matches('\n').count() as u16;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = before[line_start..].width() as u16;
+        (column, row)
+    }
+}
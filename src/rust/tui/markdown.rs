@@ -0,0 +1,134 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+
+/// 将 Markdown 渲染为终端文本
+///
+/// 支持标题、列表、引用、分隔线、代码块以及行内的粗体和代码，其余内容按原样显示
+pub fn render_markdown(text: &str) -> Text<'static> {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+
+    for raw_line in text.lines() {
+        let trimmed = raw_line.trim_start();
+
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+
+        if in_code_block {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", raw_line),
+                Style::default().fg(Color::Cyan),
+            )));
+            continue;
+        }
+
+        if let Some((level, title)) = parse_heading(trimmed) {
+            let style = if level <= 2 {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().add_modifier(Modifier::BOLD)
+            };
+            lines.push(Line::from(Span::styled(title.to_string(), style)));
+            continue;
+        }
+
+        if is_rule(trimmed) {
+            lines.push(Line::from(Span::styled(
+                "─".repeat(40),
+                Style::default().fg(Color::DarkGray),
+            )));
+            continue;
+        }
+
+        if let Some(quote) = trimmed.strip_prefix('>') {
+            let mut spans = vec![Span::styled("│ ", Style::default().fg(Color::DarkGray))];
+            spans.extend(render_inline(quote.trim_start(), Style::default().add_modifier(Modifier::ITALIC)));
+            lines.push(Line::from(spans));
+            continue;
+        }
+
+        let indent = &raw_line[..raw_line.len() - trimmed.len()];
+        if let Some(item) = ["- ", "* ", "+ "].iter().find_map(|marker| trimmed.strip_prefix(marker)) {
+            let mut spans = vec![Span::raw(format!("{}• ", indent))];
+            spans.extend(render_inline(item, Style::default()));
+            lines.push(Line::from(spans));
+            continue;
+        }
+
+        lines.push(Line::from(render_inline(raw_line, Style::default())));
+    }
+
+    Text::from(lines)
+}
+
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    line[level..].strip_prefix(' ').map(|title| (level, title.trim()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.trim_end();
+    line.len() >= 3
+        && ["-", "*", "_"].iter().any(|c| line.chars().all(|ch| ch.to_string() == *c))
+}
+
+/// 渲染行内的 `**粗体**` 和 `` `代码` ``
+fn render_inline(text: &str, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let bold = rest.find("**");
+        let code = rest.find('`');
+
+        let (start, marker, style) = match (bold, code) {
+            (Some(b), Some(c)) if c < b => (c, "`", base.fg(Color::Cyan)),
+            (Some(b), _) => (b, "**", base.add_modifier(Modifier::BOLD)),
+            (None, Some(c)) => (c, "`", base.fg(Color::Cyan)),
+            (None, None) => break,
+        };
+
+        let content_start = start + marker.len();
+        let Some(len) = rest[content_start..].find(marker) else { break };
+
+        if start > 0 {
+            spans.push(Span::styled(rest[..start].to_string(), base));
+        }
+        spans.push(Span::styled(rest[content_start..content_start + len].to_string(), style));
+        rest = &rest[content_start + len + marker.len()..];
+    }
+
+    if !rest.is_empty() {
+        spans.push(Span::styled(rest.to_string(), base));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_render_block_elements() {
+        let text = render_markdown("# 标题\n- 第一项\n> 引用\n```rust\nfn main() {}\n```\n---");
+        let rendered: Vec<String> = text.lines.iter().map(line_text).collect();
+        assert_eq!(rendered, vec!["标题", "• 第一项", "│ 引用", "  fn main() {}", &"─".repeat(40)]);
+    }
+
+    #[test]
+    fn test_render_inline_elements() {
+        let spans = render_inline("使用 **粗体** 和 `code` 以及 **未闭合", Style::default());
+        let contents: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(contents, vec!["使用 ", "粗体", " 和 ", "code", " 以及 **未闭合"]);
+        assert!(spans[1].style.add_modifier.contains(Modifier::BOLD));
+    }
+}
@@ -0,0 +1,204 @@
+//! 终端弹窗
+//!
+//! 在没有图形界面的环境（如 SSH、tmux）中，通过控制终端或指定的 tmux 窗格完成寸止交互，
+//! 返回与图形弹窗相同的结构化 JSON 响应
+
+pub mod app;
+pub mod markdown;
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyEventKind};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute};
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+use std::fs::{self, File, OpenOptions};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::config_watcher;
+use crate::mcp::types::{build_continue_response, build_send_response, PopupRequest};
+use crate::log_debug;
+use app::{TuiApp, TuiOutcome};
+
+/// 等待按键或 tmux 窗格结束时的轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 是否应使用终端弹窗代替图形弹窗
+///
+/// 配置开启回退、当前环境没有图形显示，且未启用纯 Telegram 模式时使用
+pub fn should_use_tui() -> bool {
+    let config = config_watcher().current();
+    let telegram_only = config.telegram_config.enabled && config.telegram_config.hide_frontend_popup;
+
+    config.mcp_config.zhi_tui_fallback && !telegram_only && !has_graphical_display()
+}
+
+/// 创建终端弹窗
+///
+/// 配置了 tmux 窗格时在该窗格中打开，否则使用当前进程的控制终端。
+/// 阻塞直到作答；`cancel` 被取消时关闭终端弹窗并返回取消
+pub fn create_tui_popup(request: &PopupRequest, cancel: &CancellationToken) -> Result<String> {
+    match config_watcher().current().mcp_config.zhi_tui_tmux_pane {
+        Some(pane) if !pane.trim().is_empty() => create_tmux_popup(request, pane.trim(), cancel),
+        _ => run_tui_popup(request, cancel),
+    }
+}
+
+/// 在控制终端中运行终端弹窗并返回响应
+pub fn run_tui_popup(request: &PopupRequest, cancel: &CancellationToken) -> Result<String> {
+    let tty = open_terminal()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(tty))?;
+    let _guard = TerminalGuard::enter(terminal.backend_mut())?;
+    terminal.clear()?;
+
+    let mut app = TuiApp::new(request);
+    let outcome = loop {
+        terminal.draw(|frame| app.render(frame))?;
+
+        if cancel.is_cancelled() {
+            break TuiOutcome::Cancel;
+        }
+
+        // 定期醒来刷新倒计时并检查是否被撤回
+        let poll_interval = match app.remaining() {
+            Some(remaining) if remaining.is_zero() => break TuiOutcome::Timeout,
+            Some(remaining) => remaining.min(POLL_INTERVAL),
+            None => POLL_INTERVAL,
+        };
+        if !event::poll(poll_interval)? {
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(outcome) = app.handle_key(key) {
+                break outcome;
+            }
+        }
+    };
+
+    log_debug!("终端弹窗结束: {:?}", outcome);
+
+    let request_id = Some(request.id.clone());
+    Ok(match outcome {
+        TuiOutcome::Send { user_input, selected_options } => {
+            build_send_response(user_input, selected_options, vec![], request_id, "tui")
+        }
+        TuiOutcome::Continue => build_continue_response(request_id, "tui_continue"),
+        TuiOutcome::Cancel => "CANCELLED".to_string(),
+        TuiOutcome::Timeout => request.timeout_response(),
+    })
+}
+
+/// 读取请求文件运行终端弹窗，并将响应写入输出文件（供 tmux 窗格中的子进程使用）
+pub fn run_tui_request_file(request_file: &Path, output_file: &Path) -> Result<()> {
+    let request: PopupRequest = serde_json::from_str(&fs::read_to_string(request_file)?)?;
+    let response = run_tui_popup(&request, &CancellationToken::new())?;
+    fs::write(output_file, response)?;
+    Ok(())
+}
+
+/// 在指定 tmux 窗格旁拆分出新窗格运行终端弹窗，等待作答后读取响应
+fn create_tmux_popup(request: &PopupRequest, pane: &str, cancel: &CancellationToken) -> Result<String> {
+    let temp_dir = std::env::temp_dir();
+    let request_file = temp_dir.join(format!("mcp_tui_request_{}.json", request.id));
+    let response_file = temp_dir.join(format!("mcp_tui_response_{}.json", request.id));
+    fs::write(&request_file, serde_json::to_string_pretty(request)?)?;
+
+    let channel = format!("cunzhi-tui-{}", request.id);
+    let exe = std::env::current_exe()?;
+    let command = format!(
+        "RUST_LOG=off {} --tui {} --output {}; tmux wait-for -S {}",
+        shell_quote(&exe.to_string_lossy()),
+        shell_quote(&request_file.to_string_lossy()),
+        shell_quote(&response_file.to_string_lossy()),
+        channel
+    );
+
+    let result = (|| -> Result<String> {
+        let output = Command::new("tmux")
+            .args(["split-window", "-P", "-F", "#{pane_id}", "-t", pane, &command])
+            .output()?;
+        if !output.status.success() {
+            anyhow::bail!("无法在 tmux 窗格 {} 中打开终端弹窗", pane);
+        }
+        let new_pane = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        let mut waiter = Command::new("tmux").args(["wait-for", &channel]).spawn()?;
+        while waiter.try_wait()?.is_none() {
+            if cancel.is_cancelled() {
+                // 请求被撤回：关闭弹窗窗格并结束等待
+                let _ = Command::new("tmux").args(["kill-pane", "-t", &new_pane]).status();
+                let _ = waiter.kill();
+                let _ = waiter.wait();
+                return Ok("CANCELLED".to_string());
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+
+        // 子进程异常退出时没有响应文件，按取消处理
+        Ok(fs::read_to_string(&response_file).unwrap_or_else(|_| "CANCELLED".to_string()))
+    })();
+
+    let _ = fs::remove_file(&request_file);
+    let _ = fs::remove_file(&response_file);
+    result
+}
+
+/// 打开控制终端，MCP 服务器的 stdin/stdout 被协议占用，不能直接使用
+fn open_terminal() -> Result<File> {
+    #[cfg(unix)]
+    let path = "/dev/tty";
+    #[cfg(windows)]
+    let path = "CONOUT$";
+
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .map_err(|e| anyhow::anyhow!("无法打开控制终端 {}: {}", path, e))
+}
+
+/// 进入终端弹窗时切换到备用屏幕，退出（包括出错）时恢复终端
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter(tty: &mut CrosstermBackend<File>) -> Result<Self> {
+        enable_raw_mode()?;
+        execute!(tty, EnterAlternateScreen)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        if let Ok(mut tty) = open_terminal() {
+            let _ = execute!(tty, LeaveAlternateScreen, cursor::Show);
+        }
+    }
+}
+
+/// 当前环境是否有图形显示
+pub fn has_graphical_display() -> bool {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        ["DISPLAY", "WAYLAND_DISPLAY"]
+            .iter()
+            .any(|var| std::env::var(var).map(|v| !v.is_empty()).unwrap_or(false))
+    }
+
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    {
+        true
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
@@ -2,6 +2,7 @@ use crate::config::{save_config, load_config, AppState, ReplyConfig, WindowConfi
 use crate::constants::{window, ui, validation};
 use crate::mcp::types::{build_continue_response, build_send_response, ImageAttachment, PopupRequest};
 use crate::mcp::handlers::create_tauri_popup;
+use crate::daemon::{is_daemon_mode, PopupQueue};
 use tauri::{AppHandle, Manager, State};
 
 #[tauri::command]
@@ -372,6 +373,7 @@ pub async fn set_window_settings(
 pub async fn send_mcp_response(
     response: serde_json::Value,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
     // 将响应序列化为JSON字符串
     let response_str =
@@ -385,7 +387,13 @@ pub async fn send_mcp_response(
     let args: Vec<String> = std::env::args().collect();
     let is_mcp_mode = args.len() >= 3 && args[1] == "--mcp-request";
 
-    if is_mcp_mode {
+    if is_daemon_mode() {
+        // 常驻模式：回复当前排队的请求并展示下一个
+        let request_id = response
+            .pointer("/metadata/request_id")
+            .and_then(|v| v.as_str());
+        app.state::<PopupQueue>().respond(&app, request_id, response_str);
+    } else if is_mcp_mode {
         // MCP模式：直接输出到stdout（MCP协议要求）
         println!("{}", response_str);
         std::io::Write::flush(&mut std::io::stdout())
@@ -421,6 +429,14 @@ pub fn get_cli_args() -> Result<serde_json::Value, String> {
         );
     }
 
+    // 检查是否有 --notify 参数
+    if args.len() >= 3 && args[1] == "--notify" {
+        result.insert(
+            "notify".to_string(),
+            serde_json::Value::String(args[2].clone()),
+        );
+    }
+
     Ok(serde_json::Value::Object(result))
 }
 
@@ -483,6 +499,11 @@ pub async fn open_external_url(url: String) -> Result<(), String> {
 
 #[tauri::command]
 pub async fn exit_app(app: AppHandle) -> Result<(), String> {
+    // 常驻模式下作答后不退出，由队列决定展示下一个请求或隐藏窗口
+    if is_daemon_mode() {
+        return Ok(());
+    }
+
     // 直接调用强制退出，用于程序内部的退出操作（如MCP响应后退出）
     crate::ui::exit::force_exit_app(app).await
 }
@@ -530,7 +551,7 @@ pub async fn create_test_popup(request: serde_json::Value) -> Result<String, Str
         .map_err(|e| format!("解析请求参数失败: {}", e))?;
 
     // 调用现有的popup创建函数
-    match create_tauri_popup(&popup_request) {
+    match create_tauri_popup(&popup_request).await {
         Ok(response) => Ok(response),
         Err(e) => Err(format!("创建测试popup失败: {}", e))
     }
@@ -1,4 +1,5 @@
 use crate::config::AppState;
+use crate::daemon::{is_daemon_mode, PopupQueue};
 use crate::log_important;
 use tauri::{AppHandle, Manager, WindowEvent};
 
@@ -13,6 +14,15 @@ pub fn setup_window_event_listeners(app_handle: &AppHandle) {
                 api.prevent_close();
                 
                 let app_handle = app_handle_clone.clone();
+
+                // 常驻模式：关闭窗口视为取消当前请求，进程继续在后台运行
+                if is_daemon_mode() {
+                    if let Some(window) = app_handle.get_webview_window("main") {
+                        let _ = window.hide();
+                    }
+                    app_handle.state::<PopupQueue>().respond(&app_handle, None, "CANCELLED".to_string());
+                    return;
+                }
                 
                 // 异步处理退出请求
                 tauri::async_runtime::spawn(async move {
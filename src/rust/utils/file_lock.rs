@@ -0,0 +1,39 @@
+use anyhow::Result;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+/// 跨进程的排他文件锁，离开作用域时释放
+///
+/// 锁加在旁边的 `<文件名>.lock` 上，被保护的文件本身可以被临时文件替换；
+/// 锁目录时使用目录下的 `.lock`
+pub struct FileLock {
+    file: File,
+}
+
+impl FileLock {
+    /// 获取 `path` 的排他锁，其他进程持有锁时阻塞等待
+    pub fn exclusive(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_path(path))?;
+        file.lock()?;
+        Ok(Self { file })
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+fn lock_path(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        return path.join(".lock");
+    }
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".lock");
+    path.with_file_name(file_name)
+}
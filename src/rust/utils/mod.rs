@@ -1,3 +1,5 @@
+pub mod file_lock;
 pub mod logger;
 
+pub use file_lock::FileLock;
 pub use logger::{LogConfig, init_logger, auto_init_logger};